@@ -0,0 +1,137 @@
+//! `#[builder(from_env)]` on the struct: populate a builder from environment
+//! variables or from a string map, parsing every field with `FromStr`.
+//!
+//! The key of a field is its name in upper case, or the string given by
+//! `#[builder(env = "...")]`. `Vec` fields are read as comma separated lists,
+//! and sub-builders read the keys prefixed by their own key and `_`. Errors
+//! name the full key, prefixes included.
+
+use crate::{is_builder_attr, FieldAttr};
+use proc_macro2::TokenStream as TS2;
+use quote::quote;
+use syn::{ext::IdentExt, Result};
+
+pub fn is_enabled(attrs: &[syn::Attribute]) -> Result<bool> {
+    let mut enabled = false;
+    for attr in attrs.iter().filter(|attr| is_builder_attr(attr)) {
+        let meta = attr.parse_meta()?;
+        match &meta {
+            syn::Meta::List(list)
+                if list.nested.len() == 1
+                    && matches!(
+                        list.nested.first(),
+                        Some(syn::NestedMeta::Meta(syn::Meta::Path(path)))
+                            if path.is_ident("from_env")
+                    ) =>
+            {
+                enabled = true
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    "expected `builder(from_env)`",
+                ))
+            }
+        }
+    }
+    Ok(enabled)
+}
+
+fn env_key(a: &FieldAttr) -> String {
+    match &a.opts().env {
+        Some(ls) => ls.value(),
+        None => a.ident.as_ref().unwrap().unraw().to_string().to_uppercase(),
+    }
+}
+
+// Reads one field out of `map` into `builder`, pushing a message to `errors`
+// if the value does not parse. The key is looked up, and reported, after the
+// `prefix` of `from_env` and of the enclosing sub-builders.
+fn read_field(a: &FieldAttr) -> TS2 {
+    let id = &a.ident;
+    let key = env_key(a);
+    if let Some(sub) = a.sub_builder() {
+        let key_prefix = format!("{}_", key);
+        let err_prefix = format!("field `{}`: ", id.as_ref().unwrap());
+        return quote! {
+            let sub_prefix = std::format!("{}{}", prefix, #key_prefix);
+            match #sub::__from_prefixed_map(map, &sub_prefix) {
+                std::result::Result::Ok(sub) => builder.#id = sub,
+                std::result::Result::Err(e) => errors.push(std::format!("{}{}", #err_prefix, e)),
+            }
+        };
+    }
+    let name = id.as_ref().unwrap().to_string();
+    let parsed = if a.is_vec {
+        let inner = &a.inner_type;
+        quote! {
+            value
+                .split(',')
+                .filter(|s| !s.trim().is_empty())
+                .map(|s| s.trim().parse::<#inner>())
+                .collect::<std::result::Result<std::vec::Vec<_>, _>>()
+        }
+    } else if a.is_opt {
+        let inner = &a.inner_type;
+        quote! { value.parse::<#inner>().map(std::option::Option::Some) }
+    } else {
+        let ty = &a.ty;
+        quote! { value.parse::<#ty>().map(std::option::Option::Some) }
+    };
+    quote! {
+        let key = std::format!("{}{}", prefix, #key);
+        if let std::option::Option::Some(value) = map.get(&key) {
+            match #parsed {
+                std::result::Result::Ok(v) => builder.#id = v,
+                std::result::Result::Err(e) => {
+                    errors.push(std::format!("field `{}` (`{}`): {}", #name, key, e))
+                }
+            }
+        }
+    }
+}
+
+pub fn from_env(type_attrs: &[FieldAttr]) -> TS2 {
+    let builder_inits = crate::builder_inits(type_attrs);
+    let read_fields = type_attrs.iter().map(read_field);
+    quote! {
+        pub fn from_env(
+            prefix: &str,
+        ) -> std::result::Result<Self, std::boxed::Box<dyn std::error::Error>> {
+            let map: std::collections::HashMap<std::string::String, std::string::String> =
+                std::env::vars_os()
+                    .filter_map(|(k, v)| {
+                        let k = k.into_string().ok().filter(|k| k.starts_with(prefix))?;
+                        let v = v.into_string().ok()?;
+                        std::option::Option::Some((k, v))
+                    })
+                    .collect();
+            Self::__from_prefixed_map(&map, prefix)
+        }
+
+        pub fn from_map(
+            map: &std::collections::HashMap<std::string::String, std::string::String>,
+        ) -> std::result::Result<Self, std::boxed::Box<dyn std::error::Error>> {
+            Self::__from_prefixed_map(map, "")
+        }
+
+        // The keys of the fields are `prefix` followed by their own key, so
+        // that the errors of sub-builders name the variables that were set.
+        #[doc(hidden)]
+        pub fn __from_prefixed_map(
+            map: &std::collections::HashMap<std::string::String, std::string::String>,
+            prefix: &str,
+        ) -> std::result::Result<Self, std::boxed::Box<dyn std::error::Error>> {
+            let mut builder = Self {
+                #(#builder_inits)*
+            };
+            let mut errors: std::vec::Vec<std::string::String> = std::vec::Vec::new();
+            #(#read_fields)*
+            if errors.is_empty() {
+                std::result::Result::Ok(builder)
+            } else {
+                std::result::Result::Err(errors.join("; ").into())
+            }
+        }
+    }
+}
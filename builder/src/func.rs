@@ -0,0 +1,353 @@
+//! `#[builder]` on functions: the parameters are classified exactly like the
+//! fields of a `#[derive(Builder)]` struct, and the generated builder's
+//! `call()` invokes the function with them.
+//!
+//! Methods are supported by putting `#[builder]` on the `impl` block and again
+//! on every method that should get a builder, since an attribute on a single
+//! method cannot emit the builder struct outside of the `impl`.
+
+use crate::{
+    build_values, builder_declares, builder_inits, builder_setters, check_field_attrs,
+    get_field_type_attr, is_builder_attr, FieldAttr,
+};
+use proc_macro2::{Span, TokenStream as TS2};
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, visit_mut::VisitMut, Result};
+
+pub fn expand(args: TS2, item: syn::Item) -> Result<TS2> {
+    if !args.is_empty() {
+        return Err(syn::Error::new_spanned(args, "expected `#[builder]`"));
+    }
+    match item {
+        syn::Item::Fn(f) => expand_fn(f),
+        syn::Item::Impl(i) => expand_impl(i),
+        item => Err(syn::Error::new(
+            item.span(),
+            "`#[builder]` expects a function or an impl block",
+        )),
+    }
+}
+
+// `foo_bar` -> `FooBar`
+fn to_pascal_case(s: &str) -> String {
+    s.split('_')
+        .map(|w| {
+            let mut cs = w.chars();
+            match cs.next() {
+                Some(c) => c.to_uppercase().chain(cs).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+// Names every elided lifetime (`&T`, `'_`) in a parameter type, so that the
+// type can be stored in the builder struct.
+struct NameElided {
+    lifetime: syn::Lifetime,
+    found: bool,
+}
+
+impl VisitMut for NameElided {
+    fn visit_type_reference_mut(&mut self, r: &mut syn::TypeReference) {
+        if r.lifetime.is_none() {
+            r.lifetime = Some(self.lifetime.clone());
+            self.found = true;
+        }
+        syn::visit_mut::visit_type_reference_mut(self, r);
+    }
+
+    fn visit_lifetime_mut(&mut self, l: &mut syn::Lifetime) {
+        if l.ident == "_" {
+            *l = self.lifetime.clone();
+            self.found = true;
+        }
+    }
+}
+
+// Replaces `Self` by the type of the impl block, since inside the builder
+// `Self` would name the builder itself.
+struct ReplaceSelf<'t>(&'t syn::Type);
+
+impl VisitMut for ReplaceSelf<'_> {
+    fn visit_type_mut(&mut self, ty: &mut syn::Type) {
+        match ty {
+            syn::Type::Path(tp) if tp.qself.is_none() && tp.path.is_ident("Self") => {
+                *ty = self.0.clone();
+            }
+            _ => syn::visit_mut::visit_type_mut(self, ty),
+        }
+    }
+}
+
+enum Receiver {
+    None,
+    Ref,
+    Mut,
+}
+
+struct FnBuilder {
+    receiver: Receiver,
+    type_attrs: Vec<FieldAttr>,
+    output: syn::Type,
+    has_lifetime: bool,
+}
+
+// Strips the `#[builder(...)]` attributes off the parameters (they are not
+// real attributes once the function is emitted again) and classifies them.
+// `self_ty` is the type of the enclosing impl block, if any.
+fn parse_sig(sig: &mut syn::Signature, self_ty: Option<&syn::Type>) -> Result<FnBuilder> {
+    if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "`#[builder]` does not support generic functions",
+        ));
+    }
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(syn::Error::new(
+            asyncness.span(),
+            "`#[builder]` does not support async functions",
+        ));
+    }
+    let mut receiver = Receiver::None;
+    let mut type_attrs = Vec::new();
+    let mut name_elided = NameElided {
+        lifetime: syn::Lifetime::new("'a", Span::call_site()),
+        found: false,
+    };
+    for input in sig.inputs.iter_mut() {
+        match input {
+            syn::FnArg::Receiver(r) => {
+                receiver = match (&r.reference, &r.mutability) {
+                    (Some(_), None) => Receiver::Ref,
+                    (Some(_), Some(_)) => Receiver::Mut,
+                    (None, _) => {
+                        return Err(syn::Error::new(
+                            r.span(),
+                            "`#[builder]` expects `&self` or `&mut self`",
+                        ))
+                    }
+                };
+            }
+            syn::FnArg::Typed(pt) => {
+                let ident = match &*pt.pat {
+                    syn::Pat::Ident(pi) => pi.ident.clone(),
+                    pat => {
+                        return Err(syn::Error::new(
+                            pat.span(),
+                            "`#[builder]` expects a plain identifier pattern",
+                        ))
+                    }
+                };
+                let mut ty = (*pt.ty).clone();
+                name_elided.visit_type_mut(&mut ty);
+                if let Some(self_ty) = self_ty {
+                    ReplaceSelf(self_ty).visit_type_mut(&mut ty);
+                }
+                type_attrs.push(get_field_type_attr(Some(ident), &ty, &pt.attrs));
+                pt.attrs.retain(|attr| !is_builder_attr(attr));
+            }
+        }
+    }
+    check_field_attrs(&type_attrs)?;
+    let mut output = match &sig.output {
+        syn::ReturnType::Default => syn::parse_quote!(()),
+        syn::ReturnType::Type(_, ty) => (**ty).clone(),
+    };
+    if let Some(self_ty) = self_ty {
+        ReplaceSelf(self_ty).visit_type_mut(&mut output);
+    }
+    let has_lifetime = name_elided.found || !matches!(receiver, Receiver::None);
+    Ok(FnBuilder {
+        receiver,
+        type_attrs,
+        output,
+        has_lifetime,
+    })
+}
+
+// The builder struct and its impl, shared by free functions and methods.
+// `target` is the path the function is called through, `self_ty` the type
+// borrowed by a method receiver.
+fn builder_items(
+    fb: &FnBuilder,
+    vis: &syn::Visibility,
+    builder_name: &syn::Ident,
+    self_ty: Option<&syn::Type>,
+    target: TS2,
+) -> TS2 {
+    let lifetime = if fb.has_lifetime {
+        quote! { <'a> }
+    } else {
+        quote! {}
+    };
+    let receiver_declare = match fb.receiver {
+        Receiver::None => quote! {},
+        Receiver::Ref => quote! { receiver: &'a #self_ty, },
+        Receiver::Mut => quote! { receiver: &'a mut #self_ty, },
+    };
+    let call_self = match fb.receiver {
+        Receiver::Mut => quote! { &mut self },
+        _ => quote! { &self },
+    };
+    let builder_declares = builder_declares(&fb.type_attrs);
+    let builder_setters = builder_setters(&fb.type_attrs);
+    let call_ids: Vec<_> = fb.type_attrs.iter().map(|a| &a.ident).collect();
+    let call_values = build_values(&fb.type_attrs);
+    let output = &fb.output;
+    quote! {
+        #vis struct #builder_name #lifetime {
+            #receiver_declare
+            #(#builder_declares)*
+        }
+        impl #lifetime #builder_name #lifetime {
+            #(#builder_setters)*
+
+            pub fn call(
+                #call_self,
+            ) -> std::result::Result<#output, std::boxed::Box<dyn std::error::Error>> {
+                #(let #call_ids = #call_values;)*
+                std::result::Result::Ok(#target(#(#call_ids),*))
+            }
+        }
+    }
+}
+
+fn expand_fn(mut f: syn::ItemFn) -> Result<TS2> {
+    let fb = parse_sig(&mut f.sig, None)?;
+    if !matches!(fb.receiver, Receiver::None) {
+        return Err(syn::Error::new(
+            f.sig.inputs.span(),
+            "put `#[builder]` on the enclosing impl block to build a method",
+        ));
+    }
+    let vis = &f.vis;
+    let fn_name = &f.sig.ident;
+    let entry_name = format_ident!("{}_builder", fn_name);
+    let builder_name = format_ident!("{}Builder", to_pascal_case(&fn_name.to_string()));
+    let lifetime = if fb.has_lifetime {
+        quote! { <'a> }
+    } else {
+        quote! {}
+    };
+    let builder_inits = builder_inits(&fb.type_attrs);
+    let builder_items = builder_items(&fb, vis, &builder_name, None, quote! { #fn_name });
+
+    Ok(quote! {
+        #f
+
+        #vis fn #entry_name #lifetime() -> #builder_name #lifetime {
+            #builder_name {
+                #(#builder_inits)*
+            }
+        }
+        #builder_items
+    })
+}
+
+fn expand_impl(mut i: syn::ItemImpl) -> Result<TS2> {
+    if !i.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &i.generics,
+            "`#[builder]` does not support generic impl blocks",
+        ));
+    }
+    if let Some((_, path, _)) = &i.trait_ {
+        return Err(syn::Error::new_spanned(
+            path,
+            "`#[builder]` does not support trait impl blocks",
+        ));
+    }
+    let self_ty = i.self_ty.clone();
+    let self_name = match &*self_ty {
+        syn::Type::Path(tp) if tp.qself.is_none() => tp.path.segments.last().unwrap().ident.clone(),
+        ty => {
+            return Err(syn::Error::new(
+                ty.span(),
+                "`#[builder]` expects the impl block of a named type",
+            ))
+        }
+    };
+    let mut entries = Vec::new();
+    let mut builders = Vec::new();
+    for item in i.items.iter_mut() {
+        let method = match item {
+            syn::ImplItem::Method(m) if m.attrs.iter().any(is_builder_attr) => m,
+            _ => continue,
+        };
+        for attr in method.attrs.iter().filter(|attr| is_builder_attr(attr)) {
+            if !attr.tokens.is_empty() {
+                return Err(syn::Error::new_spanned(attr, "expected `#[builder]`"));
+            }
+        }
+        method.attrs.retain(|attr| !is_builder_attr(attr));
+        let fb = parse_sig(&mut method.sig, Some(&self_ty))?;
+        let vis = &method.vis;
+        let method_name = &method.sig.ident;
+        let entry_name = format_ident!("{}_builder", method_name);
+        let builder_name = format_ident!(
+            "{}{}Builder",
+            self_name,
+            to_pascal_case(&method_name.to_string())
+        );
+        let (entry_generics, entry_receiver, receiver_init, target, ret_lifetime) =
+            match fb.receiver {
+                Receiver::None if fb.has_lifetime => (
+                    quote! { <'a> },
+                    quote! {},
+                    quote! {},
+                    quote! { #self_ty::#method_name },
+                    quote! { <'a> },
+                ),
+                Receiver::None => (
+                    quote! {},
+                    quote! {},
+                    quote! {},
+                    quote! { #self_ty::#method_name },
+                    quote! {},
+                ),
+                Receiver::Ref => (
+                    quote! {},
+                    quote! { &self },
+                    quote! { receiver: self, },
+                    quote! { self.receiver.#method_name },
+                    quote! { <'_> },
+                ),
+                Receiver::Mut => (
+                    quote! {},
+                    quote! { &mut self },
+                    quote! { receiver: self, },
+                    quote! { self.receiver.#method_name },
+                    quote! { <'_> },
+                ),
+            };
+        let builder_inits = builder_inits(&fb.type_attrs);
+        entries.push(quote! {
+            #vis fn #entry_name #entry_generics(#entry_receiver) -> #builder_name #ret_lifetime {
+                #builder_name {
+                    #receiver_init
+                    #(#builder_inits)*
+                }
+            }
+        });
+        builders.push(builder_items(
+            &fb,
+            vis,
+            &builder_name,
+            Some(&self_ty),
+            target,
+        ));
+    }
+    if entries.is_empty() {
+        return Err(syn::Error::new(
+            i.self_ty.span(),
+            "expected at least one method marked `#[builder]`",
+        ));
+    }
+    i.items
+        .extend(entries.into_iter().map(syn::ImplItem::Verbatim));
+    Ok(quote! {
+        #i
+        #(#builders)*
+    })
+}
@@ -1,11 +1,12 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TS2;
 use quote::{format_ident, quote};
-use std::cell::RefCell;
 use std::collections::HashSet;
-use std::rc::Rc;
 use syn::{parse_macro_input, spanned::Spanned, DeriveInput, Result};
 
+mod env;
+mod func;
+
 #[proc_macro_derive(Builder, attributes(builder))]
 pub fn derive(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
@@ -15,15 +16,33 @@ pub fn derive(input: TokenStream) -> TokenStream {
     }
 }
 
+#[proc_macro_attribute]
+pub fn builder(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = TS2::from(args);
+    let item = parse_macro_input!(input as syn::Item);
+    match func::expand(args, item) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
 struct FieldAttr {
     ident: Option<syn::Ident>,
-    each: Option<Result<String>>,
+    opts: Result<BuilderOpts>,
     ty: syn::Type,
     is_vec: bool,
     is_opt: bool,
     inner_type: Option<syn::Type>,
 }
 
+// Everything that can be written inside `#[builder(...)]` on a field.
+#[derive(Default)]
+struct BuilderOpts {
+    each: Option<String>,
+    sub_builder: bool,
+    env: Option<syn::LitStr>,
+}
+
 fn get_out_and_inner_type(ty: &syn::Type) -> Option<(String, syn::Type)> {
     if let syn::Type::Path(syn::TypePath {
         path: syn::Path { segments, .. },
@@ -48,46 +67,91 @@ fn get_out_and_inner_type(ty: &syn::Type) -> Option<(String, syn::Type)> {
     None
 }
 
-fn get_each_str(f: &syn::Field) -> Option<Result<String>> {
-    for attr in &f.attrs {
-        let meta = match attr.parse_meta() {
-            Ok(meta) => meta,
-            Err(e) => return Some(Err(e)),
+// `path::Server` -> `path::ServerBuilder`
+fn get_sub_builder_type(ty: &syn::Type) -> Result<syn::Path> {
+    if let syn::Type::Path(syn::TypePath { qself: None, path }) = ty {
+        let mut path = path.clone();
+        if let Some(seg) = path.segments.last_mut() {
+            if seg.arguments.is_empty() {
+                seg.ident = format_ident!("{}Builder", seg.ident);
+                return Ok(path);
+            }
+        }
+    }
+    Err(syn::Error::new_spanned(
+        ty,
+        "`builder(sub_builder)` expects a non-generic struct deriving `Builder`",
+    ))
+}
+
+fn is_builder_attr(attr: &syn::Attribute) -> bool {
+    attr.path.is_ident("builder")
+}
+
+fn get_builder_opts(attrs: &[syn::Attribute]) -> Result<BuilderOpts> {
+    let mut opts = BuilderOpts::default();
+    for attr in attrs.iter().filter(|attr| is_builder_attr(attr)) {
+        let meta = attr.parse_meta()?;
+        let list = match &meta {
+            syn::Meta::List(list) if !list.nested.is_empty() => list,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    r#"expected `builder(each = "...")`"#,
+                ))
+            }
         };
-        if let syn::Meta::List(list) = &meta {
-            if list.nested.len() == 1 {
-                let a = list.nested.first().unwrap();
-                if let syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) = a {
-                    if nv.path.get_ident().unwrap() == "each" {
-                        if let syn::Lit::Str(ls) = &nv.lit {
-                            return Some(Ok(ls.value()));
-                        }
-                    }
+        for nested in list.nested.iter() {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Str(ls),
+                    ..
+                })) if path.is_ident("each") => opts.each = Some(ls.value()),
+                syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Str(ls),
+                    ..
+                })) if path.is_ident("env") => opts.env = Some(ls.clone()),
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("sub_builder") => {
+                    opts.sub_builder = true
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        r#"expected `builder(each = "...")`"#,
+                    ))
                 }
             }
         }
-        return Some(Err(syn::Error::new(
-            meta.span(),
-            r#"expected `builder(each = "...")`"#,
-        )));
+        if opts.each.is_some() && opts.sub_builder {
+            return Err(syn::Error::new_spanned(
+                meta,
+                "`each` and `sub_builder` cannot be used together",
+            ));
+        }
     }
-    None
+    Ok(opts)
 }
 
-fn get_field_type_attr(f: &syn::Field) -> FieldAttr {
-    match get_out_and_inner_type(&f.ty) {
+fn get_field_type_attr(
+    ident: Option<syn::Ident>,
+    ty: &syn::Type,
+    attrs: &[syn::Attribute],
+) -> FieldAttr {
+    match get_out_and_inner_type(ty) {
         Some((out, inner)) => FieldAttr {
-            ident: f.ident.clone(),
-            each: get_each_str(f),
-            ty: f.ty.clone(),
+            ident,
+            opts: get_builder_opts(attrs),
+            ty: ty.clone(),
             is_vec: out == "Vec",
             is_opt: out == "Option",
             inner_type: Some(inner),
         },
         None => FieldAttr {
-            ident: f.ident.clone(),
-            each: get_each_str(f),
-            ty: f.ty.clone(),
+            ident,
+            opts: get_builder_opts(attrs),
+            ty: ty.clone(),
             is_vec: false,
             is_opt: false,
             inner_type: None,
@@ -95,75 +159,127 @@ fn get_field_type_attr(f: &syn::Field) -> FieldAttr {
     }
 }
 
-fn expand(ast: &DeriveInput) -> Result<TS2> {
-    let name = &ast.ident;
-    let builder_name = format_ident!("{}Builder", name);
-    let fields = if let syn::Data::Struct(syn::DataStruct {
-        fields: syn::Fields::Named(syn::FieldsNamed { named, .. }),
-        ..
-    }) = &ast.data
-    {
-        named
-    } else {
-        return Err(syn::Error::new(ast.span(), "Not a name struct"));
-    };
-    let type_attrs: Vec<_> = fields.iter().map(|f| get_field_type_attr(f)).collect();
+fn check_field_attrs(type_attrs: &[FieldAttr]) -> Result<()> {
     for a in type_attrs.iter() {
         if a.ident.is_none() {
             return Err(syn::Error::new(a.ty.span(), "There are no name ident"));
         }
-        if let Some(Err(e)) = &a.each {
-            return Err(e.clone());
+        match &a.opts {
+            Err(e) => return Err(e.clone()),
+            Ok(opts) if opts.sub_builder => {
+                get_sub_builder_type(&a.ty)?;
+            }
+            Ok(_) => (),
         }
     }
-    let builder_declares = type_attrs.iter().map(|a| {
-        let id = &a.ident;
-        let ty = &a.ty;
-        if a.is_opt || a.is_vec {
-            quote! { #id: #ty, }
-        } else {
-            quote! { #id: std::option::Option<#ty>, }
-        }
-    });
-    let builder_inits = type_attrs.iter().map(|a| {
-        let id = &a.ident;
-        if a.is_vec {
-            quote! { #id: Vec::new(), }
-        } else {
-            quote! { #id: None, }
-        }
-    });
-    let build_outs = type_attrs.iter().map(|a| {
-        let id = &a.ident;
-        if a.is_opt || a.is_vec {
-            quote! { #id: self.#id.clone(), }
+    Ok(())
+}
+
+impl FieldAttr {
+    // Only called after `check_field_attrs` has succeeded.
+    fn opts(&self) -> &BuilderOpts {
+        self.opts.as_ref().unwrap()
+    }
+
+    fn sub_builder(&self) -> Option<syn::Path> {
+        if self.opts().sub_builder {
+            get_sub_builder_type(&self.ty).ok()
         } else {
-            quote! { #id: self.#id.clone()?, }
+            None
         }
-    });
-    let each_names_buf = Rc::new(RefCell::new(HashSet::new()));
-    let builder_each_setters = type_attrs.iter().map(|a| {
+    }
+}
+
+fn builder_declares(type_attrs: &[FieldAttr]) -> Vec<TS2> {
+    type_attrs
+        .iter()
+        .map(|a| {
+            let id = &a.ident;
+            let ty = &a.ty;
+            if let Some(sub) = a.sub_builder() {
+                quote! { #id: #sub, }
+            } else if a.is_opt || a.is_vec {
+                quote! { #id: #ty, }
+            } else {
+                quote! { #id: std::option::Option<#ty>, }
+            }
+        })
+        .collect()
+}
+
+fn builder_inits(type_attrs: &[FieldAttr]) -> Vec<TS2> {
+    type_attrs
+        .iter()
+        .map(|a| {
+            let id = &a.ident;
+            let ty = &a.ty;
+            if a.sub_builder().is_some() {
+                quote! { #id: <#ty>::builder(), }
+            } else if a.is_vec {
+                quote! { #id: std::vec::Vec::new(), }
+            } else {
+                quote! { #id: std::option::Option::None, }
+            }
+        })
+        .collect()
+}
+
+// The value moved into the target for each field when building. Missing
+// required fields and failing sub-builders return early with an error naming
+// the field.
+fn build_values(type_attrs: &[FieldAttr]) -> Vec<TS2> {
+    type_attrs
+        .iter()
+        .map(|a| {
+            let id = &a.ident;
+            if a.sub_builder().is_some() {
+                let prefix = format!("field `{}`: ", id.as_ref().unwrap());
+                quote! {
+                    self.#id
+                        .build()
+                        .map_err(|e| std::format!("{}{}", #prefix, e))?
+                }
+            } else if a.is_opt || a.is_vec {
+                quote! { self.#id.clone() }
+            } else {
+                let msg = format!("field `{}` is not set", id.as_ref().unwrap());
+                quote! { self.#id.clone().ok_or(#msg)? }
+            }
+        })
+        .collect()
+}
+
+fn builder_setters(type_attrs: &[FieldAttr]) -> Vec<TS2> {
+    let mut each_names = HashSet::new();
+    let mut setters = Vec::new();
+    for a in type_attrs.iter() {
         let id = &a.ident;
         let ty = &a.inner_type;
-        if let Some(Ok(each_name)) = &a.each {
-            each_names_buf.borrow_mut().insert(each_name.clone());
+        if let Some(each_name) = &a.opts().each {
+            each_names.insert(each_name.clone());
             let each_name = format_ident!("{}", each_name);
-            Some(quote! {
+            setters.push(quote! {
                 pub fn #each_name(&mut self, #id: #ty) -> &mut Self {
                     self.#id.push(#id);
                     self
                 }
-            })
-        } else {
-            None
+            });
         }
-    });
-    let each_names = each_names_buf.clone();
-    let builder_setters = type_attrs.iter().map(|a| {
+    }
+    for a in type_attrs.iter() {
         let id = &a.ident;
+        if let Some(sub) = a.sub_builder() {
+            let id_mut = format_ident!("{}_mut", id.as_ref().unwrap());
+            setters.push(quote! {
+                pub fn #id_mut(&mut self) -> &mut #sub {
+                    &mut self.#id
+                }
+            });
+            continue;
+        }
         let ids: String = format!("{}", id.as_ref().unwrap());
-        if each_names.borrow().contains(&ids) {
-            return None;
+        if each_names.contains(&ids) {
+            continue;
         }
         let ty = if a.is_opt {
             a.inner_type.clone()
@@ -175,13 +291,43 @@ fn expand(ast: &DeriveInput) -> Result<TS2> {
         } else {
             quote! { self.#id = std::option::Option::Some(#id); }
         };
-        Some(quote! {
+        setters.push(quote! {
             pub fn #id(&mut self, #id: #ty) -> &mut Self {
                 #set
                 self
             }
-        })
-    });
+        });
+    }
+    setters
+}
+
+fn expand(ast: &DeriveInput) -> Result<TS2> {
+    let name = &ast.ident;
+    let builder_name = format_ident!("{}Builder", name);
+    let fields = if let syn::Data::Struct(syn::DataStruct {
+        fields: syn::Fields::Named(syn::FieldsNamed { named, .. }),
+        ..
+    }) = &ast.data
+    {
+        named
+    } else {
+        return Err(syn::Error::new(ast.span(), "Not a name struct"));
+    };
+    let type_attrs: Vec<_> = fields
+        .iter()
+        .map(|f| get_field_type_attr(f.ident.clone(), &f.ty, &f.attrs))
+        .collect();
+    check_field_attrs(&type_attrs)?;
+    let builder_declares = builder_declares(&type_attrs);
+    let builder_inits = builder_inits(&type_attrs);
+    let builder_setters = builder_setters(&type_attrs);
+    let build_ids = type_attrs.iter().map(|a| &a.ident);
+    let build_values = build_values(&type_attrs);
+    let from_env = if env::is_enabled(&ast.attrs)? {
+        env::from_env(&type_attrs)
+    } else {
+        TS2::new()
+    };
 
     let code_ts = quote! {
         impl #name {
@@ -195,15 +341,17 @@ fn expand(ast: &DeriveInput) -> Result<TS2> {
             #(#builder_declares)*
         }
         impl #builder_name {
-            #(#builder_each_setters)*
             #(#builder_setters)*
+            #from_env
 
-            pub fn build(&self) -> std::option::Option<#name> {
-                Some(#name {
-                    #(#build_outs)*
+            pub fn build(
+                &self,
+            ) -> std::result::Result<#name, std::boxed::Box<dyn std::error::Error>> {
+                std::result::Result::Ok(#name {
+                    #(#build_ids: #build_values,)*
                 })
             }
         }
     };
-    Ok(code_ts.into())
+    Ok(code_ts)
 }
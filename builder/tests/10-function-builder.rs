@@ -0,0 +1,59 @@
+// Functions with many parameters are just as awkward to call as structs with
+// many fields are to construct. Putting #[builder] on a function generates a
+// builder for its arguments, named after the function, whose call() method
+// invokes the function once every required argument has been set.
+//
+// The parameters are classified exactly like struct fields: Option parameters
+// may be left unset, Vec parameters default to empty and accept
+// #[builder(each = "...")], and everything else is required.
+//
+//     let conn = connect_builder()
+//         .host("localhost")
+//         .port(80)
+//         .call()
+//         .unwrap();
+
+use derive_builder::builder;
+
+#[derive(Debug, PartialEq)]
+pub struct Connection {
+    host: String,
+    port: u16,
+    user: Option<String>,
+    tags: Vec<String>,
+}
+
+#[builder]
+pub fn connect(
+    host: &str,
+    port: u16,
+    user: Option<String>,
+    #[builder(each = "tag")] tags: Vec<String>,
+) -> Connection {
+    Connection {
+        host: host.to_owned(),
+        port,
+        user,
+        tags,
+    }
+}
+
+#[builder]
+fn sum(lhs: u32, rhs: u32) -> u32 {
+    lhs + rhs
+}
+
+fn main() {
+    let host = String::from("localhost");
+    let conn = connect_builder()
+        .host(&host)
+        .port(80)
+        .tag("a".to_owned())
+        .tag("b".to_owned())
+        .call()
+        .unwrap();
+    assert_eq!(conn, connect("localhost", 80, None, vec!["a".to_owned(), "b".to_owned()]));
+
+    assert!(connect_builder().port(80).call().is_err());
+    assert_eq!(sum_builder().lhs(1).rhs(2).call().unwrap(), 3);
+}
@@ -0,0 +1,51 @@
+// Methods cannot carry their own builder struct because an attribute on a
+// single method is only allowed to expand to items of the surrounding impl.
+// Instead #[builder] goes on the impl block, and each method that wants a
+// builder is marked #[builder] as well. The entry point is an associated
+// function or method named `<method>_builder` returning a `<Type><Method>Builder`
+// that borrows the receiver until call().
+
+use derive_builder::builder;
+
+pub struct Counter {
+    count: u32,
+}
+
+#[builder]
+impl Counter {
+    #[builder]
+    pub fn new(start: Option<u32>) -> Self {
+        Counter {
+            count: start.unwrap_or(0),
+        }
+    }
+
+    #[builder]
+    pub fn add(&mut self, amount: u32, #[builder(each = "label")] labels: Vec<String>) -> usize {
+        self.count += amount;
+        labels.len()
+    }
+
+    #[builder]
+    pub fn peek(&self, offset: u32) -> u32 {
+        self.count + offset
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+fn main() {
+    let mut counter = Counter::new_builder().start(2).call().unwrap();
+    let labels = counter
+        .add_builder()
+        .amount(3)
+        .label("x".to_owned())
+        .call()
+        .unwrap();
+    assert_eq!(labels, 1);
+    assert_eq!(counter.count(), 5);
+    assert_eq!(counter.peek_builder().offset(1).call().unwrap(), 6);
+    assert_eq!(Counter::new_builder().call().unwrap().count(), 0);
+}
@@ -0,0 +1,48 @@
+// Nested configuration structs are easiest to fill in place. A field whose type
+// also derives Builder can be marked #[builder(sub_builder)]; the outer builder
+// then holds the nested builder and exposes it through a `<field>_mut` method
+// instead of a setter taking the whole value.
+//
+//     impl ServiceBuilder {
+//         pub fn server_mut(&mut self) -> &mut ServerBuilder {...}
+//     }
+//
+// The outer build() runs the nested build() as part of its own, and an error
+// from the nested builder is reported as an error of the outer one, prefixed
+// with the name of the field.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Debug, PartialEq)]
+pub struct Server {
+    host: String,
+    port: u16,
+}
+
+#[derive(Builder)]
+pub struct Service {
+    name: String,
+    #[builder(sub_builder)]
+    server: Server,
+}
+
+fn main() {
+    let mut builder = Service::builder();
+    builder.name("web".to_owned());
+    builder.server_mut().host("localhost".to_owned()).port(80);
+    let service = builder.build().unwrap();
+    assert_eq!(service.name, "web");
+    assert_eq!(
+        service.server,
+        Server {
+            host: "localhost".to_owned(),
+            port: 80,
+        }
+    );
+
+    let mut builder = Service::builder();
+    builder.name("web".to_owned());
+    builder.server_mut().host("localhost".to_owned());
+    let err = builder.build().err().unwrap();
+    assert_eq!(err.to_string(), "field `server`: field `port` is not set");
+}
@@ -0,0 +1,92 @@
+// Twelve-factor services read their configuration from the environment. With
+// #[builder(from_env)] on the struct, the builder gets two more constructors:
+//
+//     impl ServiceBuilder {
+//         pub fn from_env(prefix: &str) -> Result<Self, Box<dyn Error>> {...}
+//         pub fn from_map(map: &HashMap<String, String>) -> Result<Self, Box<dyn Error>> {...}
+//     }
+//
+// Every field present in the map is parsed with FromStr and stored as if its
+// setter had been called, so build() still reports missing required fields.
+// The key of a field is its name in upper case unless overridden with
+// #[builder(env = "...")]; from_env additionally prepends the prefix. Vec
+// fields are comma separated, and sub-builders read keys prefixed with their
+// own key. Parse failures are reported for every offending field, with the
+// full key that was read, sub-builder and from_env prefixes included.
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(from_env)]
+pub struct Server {
+    host: String,
+    port: u16,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+#[builder(from_env)]
+pub struct Service {
+    #[builder(env = "SERVICE_NAME")]
+    name: String,
+    workers: Option<usize>,
+    tags: Vec<String>,
+    #[builder(sub_builder)]
+    server: Server,
+}
+
+fn map(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn main() {
+    let service = ServiceBuilder::from_map(&map(&[
+        ("SERVICE_NAME", "web"),
+        ("TAGS", "a, b"),
+        ("SERVER_HOST", "localhost"),
+        ("SERVER_PORT", "80"),
+    ]))
+    .unwrap()
+    .build()
+    .unwrap();
+    assert_eq!(
+        service,
+        Service {
+            name: "web".to_owned(),
+            workers: None,
+            tags: vec!["a".to_owned(), "b".to_owned()],
+            server: Server {
+                host: "localhost".to_owned(),
+                port: 80,
+            },
+        }
+    );
+
+    let err = ServiceBuilder::from_map(&map(&[("WORKERS", "many"), ("SERVER_PORT", "-1")]))
+        .err()
+        .unwrap()
+        .to_string();
+    assert_eq!(
+        err,
+        "field `workers` (`WORKERS`): invalid digit found in string; \
+         field `server`: field `port` (`SERVER_PORT`): invalid digit found in string",
+    );
+
+    std::env::set_var("BUILDER_TEST_SERVICE_NAME", "api");
+    std::env::set_var("BUILDER_TEST_SERVER_PORT", "http");
+    let err = ServiceBuilder::from_env("BUILDER_TEST_").err().unwrap().to_string();
+    assert_eq!(
+        err,
+        "field `server`: field `port` (`BUILDER_TEST_SERVER_PORT`): invalid digit found in string",
+    );
+
+    std::env::set_var("BUILDER_TEST_HOST", "example.com");
+    std::env::set_var("BUILDER_TEST_PORT", "8080");
+    let mut builder = ServerBuilder::from_env("BUILDER_TEST_").unwrap();
+    let server = builder.port(443).build().unwrap();
+    assert_eq!(server.host, "example.com");
+    assert_eq!(server.port, 443);
+}
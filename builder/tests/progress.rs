@@ -10,4 +10,8 @@ fn tests() {
     t.pass("tests/07-repeated-field.rs");
     t.compile_fail("tests/08-unrecognized-attribute.rs");
     t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-function-builder.rs");
+    t.pass("tests/11-method-builder.rs");
+    t.pass("tests/12-sub-builder.rs");
+    t.pass("tests/13-from-env.rs");
 }
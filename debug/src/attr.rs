@@ -0,0 +1,694 @@
+//! Parsing of the `#[debug ...]` attributes on fields and on the container.
+
+use syn::{parse::Parser, punctuated::Punctuated, Field, Result, Token};
+
+pub const REDACTED: &str = "<redacted>";
+
+#[derive(Default)]
+pub struct FieldAttr {
+    // `#[debug = "..."]` or `#[debug("...")]`
+    pub format: Option<syn::LitStr>,
+    // `#[debug(skip)]`
+    pub skip: bool,
+    // `#[debug(redact)]` or `#[debug(redact = "...")]`, holding the placeholder
+    pub redact: Option<String>,
+    // `#[debug(with = "path")]`, a `fn(&T, &mut Formatter) -> fmt::Result`
+    pub with: Option<syn::ExprPath>,
+    // `#[debug(bound = "...")]`, replacing the bounds inferred from this field
+    pub bound: Option<Vec<syn::WherePredicate>>,
+    // `#[debug(rename = "...")]`, the name printed for the field
+    pub rename: Option<String>,
+    // `#[debug(flatten)]`, printing the fields of the field's own struct in
+    // place of the field
+    pub flatten: bool,
+    // `#[debug(skip_if = "path")]`, a `fn(&T) -> bool` deciding at runtime
+    // whether the field is left out
+    pub skip_if: Option<syn::ExprPath>,
+    // `#[debug(max_len = 32)]`, how many elements or characters of a
+    // collection or string are printed
+    pub max_len: Option<usize>,
+    // `#[debug(hex)]`, `#[debug(bin)]` or `#[debug(octal)]`
+    pub radix: Option<Radix>,
+}
+
+#[derive(Default)]
+pub struct ContainerAttr {
+    // Every `#[debug(bound = "...")]`, replacing all of the inferred bounds
+    pub bound: Option<Vec<syn::WherePredicate>>,
+    // `#[debug(phantom = "Tagged, Id")]`, types that are `Debug` whatever
+    // their type parameters are
+    pub phantom: Vec<syn::Path>,
+    // `#[debug(rename = "...")]`, the name printed for the struct, or for the
+    // variant when given on an enum variant
+    pub rename: Option<String>,
+    // `#[debug(rename_all = "...")]`, applied to the field names of a struct
+    // or to the variant names of an enum
+    pub rename_all: Option<RenameRule>,
+    // `#[debug(transparent)]`, printing a newtype as its only field
+    pub transparent: bool,
+    // `#[debug(flattenable)]`, generating the `__debug_fields` method that
+    // lets the struct be a `#[debug(flatten)]` field of another
+    pub flattenable: bool,
+    // `#[debug(skip_none)]`, leaving out the named fields that are `None`
+    // options or empty `Vec`s
+    pub skip_none: bool,
+    // `#[debug(max_len = 32)]`, the `max_len` of the fields that are
+    // strings, `Vec`s, `VecDeque`s, arrays or slices
+    pub max_len: Option<usize>,
+    // `#[debug(hex)]`, `#[debug(bin)]` or `#[debug(octal)]`, the radix of the
+    // fields that are primitive integers
+    pub radix: Option<Radix>,
+    // `#[debug(opaque)]` on a union, printing only its name, like `Word { .. }`
+    pub opaque: bool,
+    // `#[debug(opaque, unsafe_bytes)]`, printing the raw bytes of the union
+    // after its name; the author vouches that every byte is always initialized
+    pub unsafe_bytes: bool,
+    // `#[debug(with = "path")]` on a union, a `fn(&Self, &mut Formatter) ->
+    // fmt::Result` printing the whole union
+    pub with: Option<syn::ExprPath>,
+}
+
+// How an integer is printed by `#[debug(hex)]`, `#[debug(bin)]` or
+// `#[debug(octal)]`: `0x1f` normally, and zero padded to the width of its
+// type in groups of digits with `{:#?}`, like `0x0000_001f`.
+#[derive(Clone, Copy)]
+pub enum Radix {
+    Hex,
+    Bin,
+    Octal,
+}
+
+impl Radix {
+    fn from_path(path: &syn::Path) -> Option<Self> {
+        if path.is_ident("hex") {
+            Some(Radix::Hex)
+        } else if path.is_ident("bin") {
+            Some(Radix::Bin)
+        } else if path.is_ident("octal") {
+            Some(Radix::Octal)
+        } else {
+            None
+        }
+    }
+
+    // The formatting trait the digits are written with.
+    pub fn fmt_trait(self) -> &'static str {
+        match self {
+            Radix::Hex => "LowerHex",
+            Radix::Bin => "Binary",
+            Radix::Octal => "Octal",
+        }
+    }
+
+    pub fn spec(self) -> &'static str {
+        match self {
+            Radix::Hex => "{:x}",
+            Radix::Bin => "{:b}",
+            Radix::Octal => "{:o}",
+        }
+    }
+
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Radix::Hex => "0x",
+            Radix::Bin => "0b",
+            Radix::Octal => "0o",
+        }
+    }
+
+    // How many bits one digit holds.
+    pub fn digit_bits(self) -> usize {
+        match self {
+            Radix::Hex => 4,
+            Radix::Bin => 1,
+            Radix::Octal => 3,
+        }
+    }
+
+    // How many digits are grouped between underscores: 16 bits of hex, a
+    // byte of binary.
+    pub fn group(self) -> usize {
+        match self {
+            Radix::Hex => 4,
+            Radix::Bin => 8,
+            Radix::Octal => 3,
+        }
+    }
+}
+
+// The case conventions accepted by `rename_all`, as spelled by serde.
+#[derive(Clone, Copy)]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn from_lit(ls: &syn::LitStr) -> Result<Self> {
+        Ok(match ls.value().as_str() {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => {
+                return Err(syn::Error::new(
+                    ls.span(),
+                    "expected one of `lowercase`, `UPPERCASE`, `PascalCase`, `camelCase`, \
+                     `snake_case`, `SCREAMING_SNAKE_CASE`, `kebab-case` or \
+                     `SCREAMING-KEBAB-CASE`",
+                ))
+            }
+        })
+    }
+
+    // Splits `name` into words at underscores and at lower to upper case
+    // boundaries, so both field names and variant names convert.
+    fn words(name: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut word = String::new();
+        let mut prev_lower = false;
+        for c in name.chars() {
+            if c == '_' {
+                words.push(std::mem::take(&mut word));
+                prev_lower = false;
+                continue;
+            }
+            if c.is_uppercase() && prev_lower {
+                words.push(std::mem::take(&mut word));
+            }
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
+            word.push(c);
+        }
+        words.push(word);
+        words.retain(|w| !w.is_empty());
+        words
+    }
+
+    pub fn apply(self, name: &str) -> String {
+        let words = Self::words(name);
+        let capitalize = |w: &String| {
+            let mut cs = w.chars();
+            match cs.next() {
+                Some(f) => f
+                    .to_uppercase()
+                    .chain(cs.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            }
+        };
+        let join = |sep: &str, upper: bool| {
+            let words: Vec<String> = words
+                .iter()
+                .map(|w| {
+                    if upper {
+                        w.to_uppercase()
+                    } else {
+                        w.to_lowercase()
+                    }
+                })
+                .collect();
+            words.join(sep)
+        };
+        match self {
+            RenameRule::Lower => join("", false),
+            RenameRule::Upper => join("", true),
+            RenameRule::Pascal => words.iter().map(capitalize).collect(),
+            RenameRule::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| {
+                    if i == 0 {
+                        w.to_lowercase()
+                    } else {
+                        capitalize(w)
+                    }
+                })
+                .collect(),
+            RenameRule::Snake => join("_", false),
+            RenameRule::ScreamingSnake => join("_", true),
+            RenameRule::Kebab => join("-", false),
+            RenameRule::ScreamingKebab => join("-", true),
+        }
+    }
+}
+
+impl FieldAttr {
+    // Whether the value of the field is printed through its own `Debug` impl,
+    // rather than through a template, a radix or not at all.
+    pub fn needs_debug(&self) -> bool {
+        !self.skip
+            && self.redact.is_none()
+            && self.with.is_none()
+            && self.format.is_none()
+            && self.radix.is_none()
+    }
+}
+
+pub fn is_debug_attr(attr: &syn::Attribute) -> bool {
+    attr.path.is_ident("debug")
+}
+
+// `"T: Debug, U::Item: Debug"`; an empty string is no predicate at all.
+fn parse_bound(ls: &syn::LitStr) -> Result<Vec<syn::WherePredicate>> {
+    let parser = Punctuated::<syn::WherePredicate, Token![,]>::parse_terminated;
+    Ok(parser
+        .parse_str(&ls.value())
+        .map_err(|e| syn::Error::new(ls.span(), e))?
+        .into_iter()
+        .collect())
+}
+
+// `"Tagged, marker::Id"`
+fn parse_paths(ls: &syn::LitStr) -> Result<Vec<syn::Path>> {
+    let parser = Punctuated::<syn::Path, Token![,]>::parse_terminated;
+    Ok(parser
+        .parse_str(&ls.value())
+        .map_err(|e| syn::Error::new(ls.span(), e))?
+        .into_iter()
+        .collect())
+}
+
+// Adds `e` to the errors reported together, so that every malformed
+// attribute is reported at once rather than one per build.
+fn push_error(errors: &mut Option<syn::Error>, e: syn::Error) {
+    match errors {
+        Some(errors) => errors.combine(e),
+        None => *errors = Some(e),
+    }
+}
+
+fn set_once<T>(
+    slot: &mut Option<T>,
+    value: T,
+    tokens: impl quote::ToTokens,
+    name: &str,
+) -> Result<()> {
+    if slot.is_some() {
+        return Err(syn::Error::new_spanned(
+            tokens,
+            format!("duplicate `{}`", name),
+        ));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn parse_container_item(ca: &mut ContainerAttr, nested: &syn::NestedMeta) -> Result<()> {
+    match nested {
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Str(ls),
+            ..
+        })) if path.is_ident("bound") => ca
+            .bound
+            .get_or_insert_with(Vec::new)
+            .extend(parse_bound(ls)?),
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Str(ls),
+            ..
+        })) if path.is_ident("phantom") => ca.phantom.extend(parse_paths(ls)?),
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Str(ls),
+            ..
+        })) if path.is_ident("rename") => set_once(&mut ca.rename, ls.value(), nested, "rename")?,
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Str(ls),
+            ..
+        })) if path.is_ident("rename_all") => set_once(
+            &mut ca.rename_all,
+            RenameRule::from_lit(ls)?,
+            nested,
+            "rename_all",
+        )?,
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("transparent") => {
+            ca.transparent = true
+        }
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("flattenable") => {
+            ca.flattenable = true
+        }
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("skip_none") => {
+            ca.skip_none = true
+        }
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("opaque") => ca.opaque = true,
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("unsafe_bytes") => {
+            ca.unsafe_bytes = true
+        }
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Str(ls),
+            ..
+        })) if path.is_ident("with") => set_once(&mut ca.with, ls.parse()?, nested, "with")?,
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) if Radix::from_path(path).is_some() => {
+            set_once(
+                &mut ca.radix,
+                Radix::from_path(path).unwrap(),
+                nested,
+                "hex`, `bin` or `octal",
+            )?
+        }
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Int(li),
+            ..
+        })) if path.is_ident("max_len") => {
+            set_once(&mut ca.max_len, li.base10_parse()?, nested, "max_len")?
+        }
+        nested => {
+            return Err(syn::Error::new_spanned(
+                nested,
+                "expected one of `bound`, `phantom`, `rename`, `rename_all`, `transparent`, \
+                 `flattenable`, `skip_none`, `max_len`, `hex`, `bin`, `octal`, `opaque`, \
+                 `unsafe_bytes` or `with`",
+            ))
+        }
+    }
+    Ok(())
+}
+
+pub fn get_container_attr(attrs: &[syn::Attribute]) -> Result<ContainerAttr> {
+    let mut ca = ContainerAttr::default();
+    let mut errors = None;
+    for attr in attrs.iter().filter(|attr| is_debug_attr(attr)) {
+        let meta = match attr.parse_meta() {
+            Ok(meta) => meta,
+            Err(e) => {
+                push_error(&mut errors, e);
+                continue;
+            }
+        };
+        let list = match &meta {
+            syn::Meta::List(list) => list,
+            _ => {
+                push_error(
+                    &mut errors,
+                    syn::Error::new_spanned(meta, r#"expected `debug(...)`"#),
+                );
+                continue;
+            }
+        };
+        for nested in list.nested.iter() {
+            if let Err(e) = parse_container_item(&mut ca, nested) {
+                push_error(&mut errors, e);
+            }
+        }
+    }
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(ca),
+    }
+}
+
+fn parse_field_item(f: &Field, fa: &mut FieldAttr, nested: &syn::NestedMeta) -> Result<()> {
+    match nested {
+        syn::NestedMeta::Lit(syn::Lit::Str(ls)) => {
+            set_once(&mut fa.format, ls.clone(), nested, "template")?
+        }
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("skip") => fa.skip = true,
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) if Radix::from_path(path).is_some() => {
+            set_once(
+                &mut fa.radix,
+                Radix::from_path(path).unwrap(),
+                nested,
+                "hex`, `bin` or `octal",
+            )?
+        }
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("flatten") => {
+            if f.ident.is_none() {
+                return Err(syn::Error::new_spanned(
+                    nested,
+                    "only named fields can be flattened",
+                ));
+            }
+            fa.flatten = true
+        }
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("redact") => {
+            set_once(&mut fa.redact, REDACTED.to_owned(), nested, "redact")?
+        }
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Str(ls),
+            ..
+        })) if path.is_ident("redact") => set_once(&mut fa.redact, ls.value(), nested, "redact")?,
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Str(ls),
+            ..
+        })) if path.is_ident("with") => set_once(&mut fa.with, ls.parse()?, nested, "with")?,
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Str(ls),
+            ..
+        })) if path.is_ident("skip_if") => {
+            if f.ident.is_none() {
+                return Err(syn::Error::new_spanned(
+                    nested,
+                    "only named fields can be skipped conditionally",
+                ));
+            }
+            set_once(&mut fa.skip_if, ls.parse()?, nested, "skip_if")?
+        }
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Str(ls),
+            ..
+        })) if path.is_ident("bound") => fa
+            .bound
+            .get_or_insert_with(Vec::new)
+            .extend(parse_bound(ls)?),
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Str(ls),
+            ..
+        })) if path.is_ident("rename") => {
+            if f.ident.is_none() {
+                return Err(syn::Error::new_spanned(
+                    nested,
+                    "tuple fields have no name to rename",
+                ));
+            }
+            set_once(&mut fa.rename, ls.value(), nested, "rename")?
+        }
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Int(li),
+            ..
+        })) if path.is_ident("max_len") => {
+            set_once(&mut fa.max_len, li.base10_parse()?, nested, "max_len")?
+        }
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue { path, lit, .. }))
+            if !matches!(lit, syn::Lit::Str(_)) =>
+        {
+            return Err(syn::Error::new_spanned(
+                lit,
+                format!(
+                    "expected a string, as in `{} = \"...\"`",
+                    quote::quote!(#path)
+                ),
+            ))
+        }
+        nested => {
+            return Err(syn::Error::new_spanned(
+                nested,
+                "expected a template or one of `skip`, `skip_if`, `redact`, `with`, `bound`, \
+                 `rename`, `flatten`, `max_len`, `hex`, `bin` or `octal`",
+            ))
+        }
+    }
+    Ok(())
+}
+
+// The ways of printing a field exclude each other.
+fn check_field_attr(f: &Field, fa: &FieldAttr) -> Result<()> {
+    let given: Vec<&str> = [
+        (fa.skip, "skip"),
+        (fa.redact.is_some(), "redact"),
+        (fa.with.is_some(), "with"),
+        (fa.format.is_some(), "a template"),
+        (fa.flatten, "flatten"),
+        (fa.max_len.is_some(), "max_len"),
+        (fa.radix.is_some(), "a radix"),
+    ]
+    .iter()
+    .filter(|(given, _)| *given)
+    .map(|(_, name)| *name)
+    .collect();
+    if given.len() > 1 {
+        return Err(syn::Error::new_spanned(
+            f,
+            format!("`{}` and `{}` cannot be used together", given[0], given[1]),
+        ));
+    }
+    if fa.flatten && (fa.skip_if.is_some() || fa.rename.is_some()) {
+        return Err(syn::Error::new_spanned(
+            f,
+            "a flattened field has no name to rename and is never skipped",
+        ));
+    }
+    Ok(())
+}
+
+pub fn get_debug_attr(f: &Field) -> Result<FieldAttr> {
+    let mut fa = FieldAttr::default();
+    let mut errors = None;
+    for attr in f.attrs.iter().filter(|attr| is_debug_attr(attr)) {
+        let result = attr.parse_meta().and_then(|meta| match &meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                lit: syn::Lit::Str(ls),
+                ..
+            }) => set_once(&mut fa.format, ls.clone(), &meta, "template"),
+            syn::Meta::List(list) => {
+                for nested in list.nested.iter() {
+                    if let Err(e) = parse_field_item(f, &mut fa, nested) {
+                        push_error(&mut errors, e);
+                    }
+                }
+                Ok(())
+            }
+            meta => Err(syn::Error::new_spanned(
+                meta,
+                r#"expected `debug = "..."` or `debug(...)`"#,
+            )),
+        });
+        if let Err(e) = result {
+            push_error(&mut errors, e);
+        }
+    }
+    if errors.is_none() {
+        if let Err(e) = check_field_attr(f, &fa) {
+            push_error(&mut errors, e);
+        }
+    }
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(fa),
+    }
+}
+
+// On an enum variant only `#[debug(rename = "...")]` is meaningful.
+pub fn get_variant_attr(v: &syn::Variant) -> Result<ContainerAttr> {
+    let va = get_container_attr(&v.attrs)?;
+    if va.bound.is_some()
+        || !va.phantom.is_empty()
+        || va.rename_all.is_some()
+        || va.transparent
+        || va.flattenable
+        || va.skip_none
+        || va.max_len.is_some()
+        || va.radix.is_some()
+        || va.opaque
+        || va.unsafe_bytes
+        || va.with.is_some()
+    {
+        let attr = v.attrs.iter().find(|attr| is_debug_attr(attr)).unwrap();
+        return Err(syn::Error::new_spanned(
+            attr,
+            r#"expected `debug(rename = "...")` on a variant"#,
+        ));
+    }
+    Ok(va)
+}
+
+// Parses every `debug` attribute of the type up front, so that all of the
+// malformed ones are reported together before any code is generated.
+pub fn check_attrs(ast: &syn::DeriveInput) -> Result<()> {
+    let mut errors = None;
+    let mut check = |result: Result<()>| {
+        if let Err(e) = result {
+            push_error(&mut errors, e);
+        }
+    };
+    check(get_container_attr(&ast.attrs).map(drop));
+    match &ast.data {
+        syn::Data::Struct(data) => {
+            for f in data.fields.iter() {
+                check(get_debug_attr(f).map(drop));
+            }
+        }
+        syn::Data::Enum(data) => {
+            for v in data.variants.iter() {
+                check(get_variant_attr(v).map(drop));
+                for f in v.fields.iter() {
+                    check(get_debug_attr(f).map(drop));
+                }
+            }
+        }
+        syn::Data::Union(_) => (),
+    }
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(()),
+    }
+}
+
+// `#[eq(...)]`, read by both `CustomPartialEq` and `CustomHash` so that the
+// two always agree on the fields that take part.
+#[derive(Default)]
+pub struct EqAttr {
+    // `#[eq(skip)]` on a field
+    pub skip: bool,
+    // `#[eq(bound = "...")]`, replacing the inferred bounds of the container
+    // or of the field
+    pub bound: Option<Vec<syn::WherePredicate>>,
+}
+
+fn parse_eq_item(ea: &mut EqAttr, nested: &syn::NestedMeta, is_field: bool) -> Result<()> {
+    match nested {
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) if is_field && path.is_ident("skip") => {
+            ea.skip = true
+        }
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            lit: syn::Lit::Str(ls),
+            ..
+        })) if path.is_ident("bound") => ea
+            .bound
+            .get_or_insert_with(Vec::new)
+            .extend(parse_bound(ls)?),
+        nested if is_field => {
+            return Err(syn::Error::new_spanned(
+                nested,
+                "expected `skip` or `bound`",
+            ))
+        }
+        nested => return Err(syn::Error::new_spanned(nested, "expected `bound`")),
+    }
+    Ok(())
+}
+
+pub fn get_eq_attr(attrs: &[syn::Attribute], is_field: bool) -> Result<EqAttr> {
+    let mut ea = EqAttr::default();
+    let mut errors = None;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("eq")) {
+        let result = attr.parse_meta().and_then(|meta| match &meta {
+            syn::Meta::List(list) => {
+                for nested in list.nested.iter() {
+                    if let Err(e) = parse_eq_item(&mut ea, nested, is_field) {
+                        push_error(&mut errors, e);
+                    }
+                }
+                Ok(())
+            }
+            meta => Err(syn::Error::new_spanned(meta, "expected `eq(...)`")),
+        });
+        if let Err(e) = result {
+            push_error(&mut errors, e);
+        }
+    }
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(ea),
+    }
+}
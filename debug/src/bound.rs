@@ -0,0 +1,189 @@
+//! Inference of the `Debug` (or other formatting trait) bounds needed by the
+//! generated impl.
+//!
+//! For every type parameter `T`, each field type is searched for the places
+//! where `T` ends up being formatted: `T` itself, an associated type like
+//! `T::Value` or `<T as Trait>::Value`, inside tuples, arrays, slices,
+//! references and the generic arguments of other types. Function pointers,
+//! trait objects and raw pointers format without looking at `T`, and so do
+//! `PhantomData<T>` and the marker types named by `#[debug(phantom = "...")]`.
+
+use proc_macro2::{TokenStream as TS2, TokenTree};
+use quote::{format_ident, quote};
+use syn::{parse_quote, Field};
+
+// Every field of the struct, or of all the variants of the enum.
+type FieldList<'a> = [&'a Field];
+
+fn mentions(id: &syn::Ident, ts: TS2) -> bool {
+    ts.into_iter().any(|tt| match tt {
+        TokenTree::Ident(i) => i == *id,
+        TokenTree::Group(g) => mentions(id, g.stream()),
+        _ => false,
+    })
+}
+
+fn segment_idents(path: &syn::Path) -> Vec<&syn::Ident> {
+    path.segments.iter().map(|seg| &seg.ident).collect()
+}
+
+// `PhantomData` is recognized unqualified, as `marker::PhantomData` or by its
+// full path in `core` or `std`; a marker given as a single name matches any path ending
+// in that name, otherwise the whole path has to match.
+fn is_phantom(path: &syn::Path, phantoms: &[syn::Path]) -> bool {
+    let idents = segment_idents(path);
+    let phantom_data = match idents.as_slice() {
+        [pd] => *pd == "PhantomData",
+        [marker, pd] => *marker == "marker" && *pd == "PhantomData",
+        [krate, marker, pd] => {
+            (*krate == "core" || *krate == "std") && *marker == "marker" && *pd == "PhantomData"
+        }
+        _ => false,
+    };
+    phantom_data
+        || phantoms.iter().any(|phantom| {
+            let phantom_idents = segment_idents(phantom);
+            if phantom_idents.len() == 1 {
+                idents.last() == phantom_idents.last()
+            } else {
+                idents == phantom_idents
+            }
+        })
+}
+
+// Pushes every type that needs `Debug` for `ty` to be `Debug` with respect
+// to the type parameter `id`.
+fn get_ty_bound(
+    id: &syn::Ident,
+    ty: &syn::Type,
+    phantoms: &[syn::Path],
+    bounds: &mut Vec<syn::Type>,
+) {
+    match ty {
+        syn::Type::Path(syn::TypePath {
+            qself: Some(qself), ..
+        }) => {
+            let qself_ty = &qself.ty;
+            if mentions(id, quote!(#qself_ty)) {
+                bounds.push(ty.clone());
+            }
+        }
+        syn::Type::Path(syn::TypePath { qself: None, path }) => {
+            let segments = &path.segments;
+            let f = match segments.first() {
+                Some(f) => f,
+                None => return,
+            };
+            if f.ident == *id {
+                bounds.push(ty.clone());
+                return;
+            }
+            if is_phantom(path, phantoms) {
+                return;
+            }
+            for seg in segments.iter() {
+                if let syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments {
+                    args,
+                    ..
+                }) = &seg.arguments
+                {
+                    for arg in args {
+                        if let syn::GenericArgument::Type(ty) = arg {
+                            get_ty_bound(id, ty, phantoms, bounds);
+                        }
+                    }
+                }
+            }
+        }
+        syn::Type::Tuple(tuple) => {
+            for elem in tuple.elems.iter() {
+                get_ty_bound(id, elem, phantoms, bounds);
+            }
+        }
+        syn::Type::Array(array) => get_ty_bound(id, &array.elem, phantoms, bounds),
+        syn::Type::Slice(slice) => get_ty_bound(id, &slice.elem, phantoms, bounds),
+        syn::Type::Reference(reference) => get_ty_bound(id, &reference.elem, phantoms, bounds),
+        syn::Type::Paren(paren) => get_ty_bound(id, &paren.elem, phantoms, bounds),
+        syn::Type::Group(group) => get_ty_bound(id, &group.elem, phantoms, bounds),
+        _ => (),
+    }
+}
+
+fn get_tp_trait_bound(
+    tp: &syn::TypeParam,
+    fields: &FieldList,
+    phantoms: &[syn::Path],
+) -> Vec<syn::Type> {
+    let mut bounds = Vec::new();
+    for f in fields.iter() {
+        get_ty_bound(&tp.ident, &f.ty, phantoms, &mut bounds);
+    }
+    bounds
+}
+
+// `bound_trait` is the formatting trait the fields are printed through,
+// `Debug` for `CustomDebug`.
+pub fn add_bounds_to_generics(
+    g: &syn::Generics,
+    fields: &FieldList,
+    phantoms: &[syn::Path],
+    bound_trait: &syn::Path,
+) -> syn::Generics {
+    let mut mg = g.clone();
+    let where_clause = mg.make_where_clause();
+    let mut seen = Vec::new();
+    for p in &g.params {
+        if let syn::GenericParam::Type(tp) = p {
+            for ty in get_tp_trait_bound(tp, fields, phantoms).into_iter() {
+                let key = quote!(#ty).to_string();
+                if !seen.contains(&key) {
+                    seen.push(key);
+                    where_clause
+                        .predicates
+                        .push(parse_quote!(#ty: #bound_trait));
+                }
+            }
+        }
+    }
+    mg
+}
+
+// Explicit `#[debug(bound = "...")]` predicates, from the container or fields.
+pub fn add_bounds_to_generics_from_attr(
+    g: &syn::Generics,
+    bounds: &[syn::WherePredicate],
+) -> syn::Generics {
+    let mut mg = g.clone();
+    mg.make_where_clause()
+        .predicates
+        .extend(bounds.iter().cloned());
+    mg
+}
+
+// The bounds for fields printed through a template, each with the formatting
+// trait its placeholder asks for, like `LowerHex` for `{:x}`.
+pub fn add_fmt_bounds_to_generics(
+    g: &syn::Generics,
+    bounded: &[(&'static str, &Field)],
+    phantoms: &[syn::Path],
+) -> syn::Generics {
+    let mut traits: Vec<&str> = bounded.iter().map(|(t, _)| *t).collect();
+    traits.sort();
+    traits.dedup();
+    let mut g = g.clone();
+    for fmt_trait in traits {
+        let fields: Vec<&Field> = bounded
+            .iter()
+            .filter(|(t, _)| *t == fmt_trait)
+            .map(|(_, f)| *f)
+            .collect();
+        let fmt_trait = format_ident!("{}", fmt_trait);
+        g = add_bounds_to_generics(
+            &g,
+            &fields,
+            phantoms,
+            &parse_quote!(::core::fmt::#fmt_trait),
+        );
+    }
+    g
+}
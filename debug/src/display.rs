@@ -0,0 +1,154 @@
+//! `#[derive(CustomDisplay)]`: a `Display` impl written out by a
+//! `#[display("...")]` template on the struct, or on each enum variant.
+//!
+//! The placeholders of the template name the fields, `{name}` or `{0}`, and
+//! the bounds on the type parameters are inferred from the formatting trait
+//! each placeholder asks for, so `{id:x}` bounds the type of `id` by
+//! `LowerHex` rather than `Display`.
+
+use crate::{bound::add_fmt_bounds_to_generics, field_binding, template, variant_pattern};
+use proc_macro2::{Span, TokenStream as TS2};
+use quote::quote;
+use syn::{ext::IdentExt, DeriveInput, Field, Result};
+
+fn is_display_attr(attr: &syn::Attribute) -> bool {
+    attr.path.is_ident("display")
+}
+
+// The `"..."` of `#[display("...")]`, if there is one.
+fn get_template(attrs: &[syn::Attribute]) -> Result<Option<syn::LitStr>> {
+    let mut template = None;
+    for attr in attrs.iter().filter(|attr| is_display_attr(attr)) {
+        let meta = attr.parse_meta()?;
+        match &meta {
+            syn::Meta::List(list) if list.nested.len() == 1 => match list.nested.first() {
+                Some(syn::NestedMeta::Lit(syn::Lit::Str(ls))) if template.is_none() => {
+                    template = Some(ls.clone())
+                }
+                Some(syn::NestedMeta::Lit(syn::Lit::Str(ls))) => {
+                    return Err(syn::Error::new(ls.span(), "duplicate `display` template"))
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        r#"expected `display("...")`"#,
+                    ))
+                }
+            },
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    r#"expected `display("...")`"#,
+                ))
+            }
+        }
+    }
+    Ok(template)
+}
+
+// Writes `fields` through `template`, with every placeholder pointed at the
+// binding of its field. The referenced fields are added to `bounded` under
+// the trait they are formatted with.
+fn write_fields<'a>(
+    ls: &syn::LitStr,
+    fields: &'a syn::Fields,
+    bounded: &mut Vec<(&'static str, &'a Field)>,
+) -> Result<TS2> {
+    let placeholders = template::placeholders(ls)?;
+    let mut renamed = Vec::new();
+    let mut bindings = Vec::new();
+    for p in placeholders.iter() {
+        template::check_spec(ls, p)?;
+        let found = fields.iter().enumerate().find(|(i, f)| match &f.ident {
+            Some(id) => id.unraw() == p.arg,
+            None => i.to_string() == p.arg,
+        });
+        let (i, f) = match found {
+            Some(found) => found,
+            None if p.arg.is_empty() => {
+                return Err(syn::Error::new(
+                    template::subspan(ls, p.arg_range.start - 1..p.arg_range.end + 1),
+                    "every placeholder has to name a field, like `{name}` or `{0}`",
+                ))
+            }
+            None => {
+                return Err(syn::Error::new(
+                    template::subspan(ls, p.arg_range.clone()),
+                    format!("there is no field `{}`", p.arg),
+                ))
+            }
+        };
+        bounded.push((p.fmt_trait(), f));
+        let binding = field_binding(i, f);
+        if !bindings.contains(&binding) {
+            bindings.push(binding.clone());
+        }
+        renamed.push(binding.to_string());
+    }
+    let mut renamed = renamed.into_iter();
+    let ls = template::rename_args(ls, &placeholders, |_| renamed.next().unwrap());
+    // The fields themselves rather than the references bound by the `match`,
+    // so that `{:p}` prints the pointer held by the field.
+    Ok(quote! { ::core::write!(fmt, #ls, #(#bindings = *#bindings),*) })
+}
+
+pub fn expand(ast: &DeriveInput) -> Result<TS2> {
+    let name = &ast.ident;
+    let mut bounded = Vec::new();
+    let body = match &ast.data {
+        syn::Data::Struct(data) => {
+            let ls = get_template(&ast.attrs)?.ok_or_else(|| {
+                syn::Error::new(
+                    Span::call_site(),
+                    r#"expected a `#[display("...")]` template on the struct"#,
+                )
+            })?;
+            let pattern = variant_pattern(&data.fields);
+            let write = write_fields(&ls, &data.fields, &mut bounded)?;
+            quote! {
+                match self {
+                    Self #pattern => #write,
+                }
+            }
+        }
+        syn::Data::Enum(data) if data.variants.is_empty() => quote! { match *self {} },
+        syn::Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for v in data.variants.iter() {
+                let vid = &v.ident;
+                let pattern = variant_pattern(&v.fields);
+                let write = match (get_template(&v.attrs)?, &v.fields) {
+                    (Some(ls), fields) => write_fields(&ls, fields, &mut bounded)?,
+                    (None, syn::Fields::Unit) => {
+                        let vname = format!("{}", vid);
+                        quote! { fmt.write_str(#vname) }
+                    }
+                    (None, _) => {
+                        return Err(syn::Error::new(
+                            vid.span(),
+                            r#"expected a `#[display("...")]` template on the variant"#,
+                        ))
+                    }
+                };
+                arms.push(quote! { Self::#vid #pattern => #write, });
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new(Span::call_site(), "Not a struct or enum"));
+        }
+    };
+    let g = add_fmt_bounds_to_generics(&ast.generics, &bounded, &[]);
+    let (impl_generics, ty_generics, where_clause) = g.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
@@ -0,0 +1,222 @@
+//! `#[derive(CustomPartialEq)]` and `#[derive(CustomHash)]`: comparison and
+//! hashing of every field but those marked `#[eq(skip)]`, typically the same
+//! caches and timestamps that are skipped from `Debug`.
+//!
+//! The bounds are inferred like those of `CustomDebug`, with `PartialEq` or
+//! `Hash` in place of `Debug`, from the fields that take part.
+
+use crate::{
+    attr::{get_eq_attr, EqAttr},
+    bound::{add_bounds_to_generics, add_bounds_to_generics_from_attr},
+    prefixed_binding,
+};
+use proc_macro2::TokenStream as TS2;
+use quote::quote;
+use syn::{DeriveInput, Field, Result};
+
+// One field taking part, as reached from `self` and from `other`.
+struct EqField<'a> {
+    field: &'a Field,
+    attr: EqAttr,
+    this: TS2,
+    other: TS2,
+}
+
+fn eq_fields_of_self(fields: &syn::Fields) -> Result<Vec<EqField<'_>>> {
+    let mut eq_fields = Vec::new();
+    for (i, f) in fields.iter().enumerate() {
+        let attr = get_eq_attr(&f.attrs, true)?;
+        if attr.skip {
+            continue;
+        }
+        let member = match &f.ident {
+            Some(id) => syn::Member::Named(id.clone()),
+            None => syn::Member::Unnamed(i.into()),
+        };
+        eq_fields.push(EqField {
+            field: f,
+            attr,
+            this: quote! { &self.#member },
+            other: quote! { &other.#member },
+        });
+    }
+    Ok(eq_fields)
+}
+
+// `{ x: __self_x, y: _ }` or `(__self_0, _)` to destructure an enum variant
+// into `prefix` bindings, ignoring the skipped fields.
+fn variant_pattern(prefix: &str, fields: &syn::Fields) -> Result<TS2> {
+    let mut bindings = Vec::new();
+    for (i, f) in fields.iter().enumerate() {
+        if get_eq_attr(&f.attrs, true)?.skip {
+            bindings.push(quote! { _ });
+        } else {
+            let binding = prefixed_binding(prefix, i, f);
+            bindings.push(quote! { #binding });
+        }
+    }
+    Ok(match fields {
+        syn::Fields::Named(named) => {
+            let ids = named.named.iter().map(|f| &f.ident);
+            quote! { { #(#ids: #bindings),* } }
+        }
+        syn::Fields::Unnamed(_) => quote! { ( #(#bindings),* ) },
+        syn::Fields::Unit => quote! {},
+    })
+}
+
+fn eq_fields_of_binding(fields: &syn::Fields) -> Result<Vec<EqField<'_>>> {
+    let mut eq_fields = Vec::new();
+    for (i, f) in fields.iter().enumerate() {
+        let attr = get_eq_attr(&f.attrs, true)?;
+        if attr.skip {
+            continue;
+        }
+        let this = prefixed_binding("__self", i, f);
+        let other = prefixed_binding("__other", i, f);
+        eq_fields.push(EqField {
+            field: f,
+            attr,
+            this: quote! { #this },
+            other: quote! { #other },
+        });
+    }
+    Ok(eq_fields)
+}
+
+// The generics with `bound_trait` on what the fields need, unless the
+// container or the fields give their own `#[eq(bound = "...")]`.
+fn eq_generics(
+    ast: &DeriveInput,
+    eq_fields: &[EqField],
+    bound_trait: &syn::Path,
+) -> Result<syn::Generics> {
+    let container = get_eq_attr(&ast.attrs, false)?;
+    let g = match &container.bound {
+        Some(bounds) => add_bounds_to_generics_from_attr(&ast.generics, bounds),
+        None => {
+            let fields: Vec<&Field> = eq_fields
+                .iter()
+                .filter(|ef| ef.attr.bound.is_none())
+                .map(|ef| ef.field)
+                .collect();
+            add_bounds_to_generics(&ast.generics, &fields, &[], bound_trait)
+        }
+    };
+    let bounds: Vec<syn::WherePredicate> = eq_fields
+        .iter()
+        .flat_map(|ef| ef.attr.bound.iter().flatten().cloned())
+        .collect();
+    Ok(add_bounds_to_generics_from_attr(&g, &bounds))
+}
+
+fn not_union(ast: &DeriveInput) -> syn::Error {
+    syn::Error::new(
+        ast.ident.span(),
+        "the fields of a union cannot be compared or hashed",
+    )
+}
+
+pub fn expand_partial_eq(ast: &DeriveInput) -> Result<TS2> {
+    let name = &ast.ident;
+    let mut all_fields = Vec::new();
+    let body = match &ast.data {
+        syn::Data::Struct(data) => {
+            let eq_fields = eq_fields_of_self(&data.fields)?;
+            let compares = eq_fields.iter().map(|ef| {
+                let (this, other) = (&ef.this, &ef.other);
+                quote! { && ::core::cmp::PartialEq::eq(#this, #other) }
+            });
+            let body = quote! { true #(#compares)* };
+            all_fields.extend(eq_fields);
+            body
+        }
+        syn::Data::Enum(data) if data.variants.is_empty() => quote! { match *self {} },
+        syn::Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for v in data.variants.iter() {
+                let vid = &v.ident;
+                let this = variant_pattern("__self", &v.fields)?;
+                let other = variant_pattern("__other", &v.fields)?;
+                let eq_fields = eq_fields_of_binding(&v.fields)?;
+                let compares = eq_fields.iter().map(|ef| {
+                    let (this, other) = (&ef.this, &ef.other);
+                    quote! { && ::core::cmp::PartialEq::eq(#this, #other) }
+                });
+                arms.push(quote! {
+                    (Self::#vid #this, Self::#vid #other) => true #(#compares)*,
+                });
+                all_fields.extend(eq_fields);
+            }
+            let rest = if data.variants.len() > 1 {
+                quote! { _ => false, }
+            } else {
+                TS2::new()
+            };
+            quote! {
+                match (self, other) {
+                    #(#arms)*
+                    #rest
+                }
+            }
+        }
+        syn::Data::Union(_) => return Err(not_union(ast)),
+    };
+    let g = eq_generics(ast, &all_fields, &syn::parse_quote!(::core::cmp::PartialEq))?;
+    let (impl_generics, ty_generics, where_clause) = g.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::core::cmp::PartialEq for #name #ty_generics #where_clause {
+            fn eq(&self, other: &Self) -> bool {
+                #body
+            }
+        }
+    })
+}
+
+pub fn expand_hash(ast: &DeriveInput) -> Result<TS2> {
+    let name = &ast.ident;
+    let mut all_fields = Vec::new();
+    let body = match &ast.data {
+        syn::Data::Struct(data) => {
+            let eq_fields = eq_fields_of_self(&data.fields)?;
+            let hashes = eq_fields.iter().map(|ef| {
+                let this = &ef.this;
+                quote! { ::core::hash::Hash::hash(#this, state); }
+            });
+            let body = quote! { #(#hashes)* };
+            all_fields.extend(eq_fields);
+            body
+        }
+        syn::Data::Enum(data) if data.variants.is_empty() => quote! { match *self {} },
+        syn::Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for v in data.variants.iter() {
+                let vid = &v.ident;
+                let this = variant_pattern("__self", &v.fields)?;
+                let eq_fields = eq_fields_of_binding(&v.fields)?;
+                let hashes = eq_fields.iter().map(|ef| {
+                    let this = &ef.this;
+                    quote! { ::core::hash::Hash::hash(#this, state); }
+                });
+                arms.push(quote! { Self::#vid #this => { #(#hashes)* } });
+                all_fields.extend(eq_fields);
+            }
+            quote! {
+                ::core::hash::Hash::hash(&::core::mem::discriminant(self), state);
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        syn::Data::Union(_) => return Err(not_union(ast)),
+    };
+    let g = eq_generics(ast, &all_fields, &syn::parse_quote!(::core::hash::Hash))?;
+    let (impl_generics, ty_generics, where_clause) = g.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::core::hash::Hash for #name #ty_generics #where_clause {
+            fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) {
+                #body
+            }
+        }
+    })
+}
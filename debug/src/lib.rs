@@ -1,7 +1,24 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TS2;
-use quote::quote;
-use syn::{parse_macro_input, parse_quote, spanned::Spanned, DeriveInput, Field, Result};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    ext::IdentExt, parse_macro_input, parse_quote, spanned::Spanned, DeriveInput, Field, Result,
+};
+
+mod attr;
+mod bound;
+mod display;
+mod eq;
+mod template;
+#[cfg(test)]
+mod tests;
+mod valuable;
+
+use attr::{
+    check_attrs, get_container_attr, get_debug_attr, get_variant_attr, ContainerAttr, FieldAttr,
+    RenameRule,
+};
+use bound::{add_bounds_to_generics, add_bounds_to_generics_from_attr, add_fmt_bounds_to_generics};
 
 #[proc_macro_derive(CustomDebug, attributes(debug))]
 pub fn derive(input: TokenStream) -> TokenStream {
@@ -12,160 +29,820 @@ pub fn derive(input: TokenStream) -> TokenStream {
     }
 }
 
-fn get_debug_attr(f: &Field) -> Option<Result<String>> {
-    for attr in &f.attrs {
-        let meta = match attr.parse_meta() {
-            Err(e) => return Some(Err(e)),
-            Ok(meta) => meta,
+#[proc_macro_derive(CustomDisplay, attributes(display))]
+pub fn derive_display(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    match display::expand(&ast) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(CustomPartialEq, attributes(eq))]
+pub fn derive_partial_eq(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    match eq::expand_partial_eq(&ast) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(CustomHash, attributes(eq))]
+pub fn derive_hash(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    match eq::expand_hash(&ast) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(CustomValuable, attributes(debug, valuable))]
+pub fn derive_valuable(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    match valuable::expand(&ast) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+// How to reach one field from inside `fmt`: `self.x` for structs, or the
+// binding introduced by the `match` for enum variants.
+struct FieldAccess<'a> {
+    field: &'a Field,
+    attr: FieldAttr,
+    access: TS2,
+    // The `format_args!` of a `#[debug = "..."]` template, once resolved
+    formatted: Option<TS2>,
+}
+
+fn field_accesses_of_self(fields: &syn::Fields) -> Result<Vec<FieldAccess<'_>>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let member = match &f.ident {
+                Some(id) => syn::Member::Named(id.clone()),
+                None => syn::Member::Unnamed(i.into()),
+            };
+            Ok(FieldAccess {
+                field: f,
+                attr: get_debug_attr(f)?,
+                access: quote! { &self.#member },
+                formatted: None,
+            })
+        })
+        .collect()
+}
+
+// The bindings are named after the field (`__self_name`) or its index
+// (`__self_0`), so they never shadow `fmt`.
+fn field_binding(i: usize, f: &Field) -> syn::Ident {
+    prefixed_binding("__self", i, f)
+}
+
+fn prefixed_binding(prefix: &str, i: usize, f: &Field) -> syn::Ident {
+    match &f.ident {
+        Some(id) => format_ident!("{}_{}", prefix, id.unraw()),
+        None => format_ident!("{}_{}", prefix, i),
+    }
+}
+
+fn field_accesses_of_binding(fields: &syn::Fields) -> Result<Vec<FieldAccess<'_>>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let binding = field_binding(i, f);
+            Ok(FieldAccess {
+                field: f,
+                attr: get_debug_attr(f)?,
+                access: quote! { #binding },
+                formatted: None,
+            })
+        })
+        .collect()
+}
+
+// `{ x: __self_x, .. }` or `(__self_0, ..)` to destructure an enum variant.
+fn variant_pattern(fields: &syn::Fields) -> TS2 {
+    let bindings = fields.iter().enumerate().map(|(i, f)| field_binding(i, f));
+    match fields {
+        syn::Fields::Named(named) => {
+            let ids = named.named.iter().map(|f| &f.ident);
+            quote! { { #(#ids: #bindings),* } }
+        }
+        syn::Fields::Unnamed(_) => quote! { ( #(#bindings),* ) },
+        syn::Fields::Unit => quote! {},
+    }
+}
+
+// Only the fields whose value is printed need a `Debug` bound, and only
+// those without an explicit bound have it inferred.
+fn bound_fields<'a>(accesses: &[FieldAccess<'a>]) -> Vec<&'a Field> {
+    accesses
+        .iter()
+        .filter(|fa| fa.attr.needs_debug() && fa.attr.bound.is_none())
+        .map(|fa| fa.field)
+        .collect()
+}
+
+fn field_bounds(accesses: &[FieldAccess]) -> Vec<syn::WherePredicate> {
+    accesses
+        .iter()
+        .flat_map(|fa| fa.attr.bound.iter().flatten().cloned())
+        .collect()
+}
+
+// Resolves the `#[debug = "..."]` templates against the fields: `{}` and
+// `{0}` are the value of the field itself, `{self.name}` or `{self.0}` the
+// value of any field. Every placeholder becomes a named argument, checked
+// here so that mistakes point into the template rather than at generated
+// code. Returns the formatting trait each field printed through a template
+// or a radix needs.
+fn resolve_formats<'a>(accesses: &mut [FieldAccess<'a>]) -> Result<Vec<(&'static str, &'a Field)>> {
+    let mut bounded = Vec::new();
+    for fa in accesses.iter() {
+        if let (Some(radix), None) = (fa.attr.radix, &fa.attr.bound) {
+            bounded.push((radix.fmt_trait(), fa.field));
+        }
+    }
+    for i in 0..accesses.len() {
+        let ls = match &accesses[i].attr.format {
+            Some(ls) => ls.clone(),
+            None => continue,
         };
-        if let syn::Meta::NameValue(nv) = &meta {
-            if nv.path.get_ident().unwrap() == "debug" {
-                if let syn::Lit::Str(ls) = &nv.lit {
-                    return Some(Ok(ls.value()));
-                }
+        let placeholders = template::placeholders(&ls)?;
+        let mut args: Vec<(syn::Ident, TS2)> = Vec::new();
+        let mut names = Vec::new();
+        for p in placeholders.iter() {
+            template::check_spec(&ls, p)?;
+            let j =
+                match p.arg.strip_prefix("self.") {
+                    _ if p.arg.is_empty() || p.arg == "0" => i,
+                    Some(member) => accesses
+                        .iter()
+                        .enumerate()
+                        .position(|(j, fa)| match &fa.field.ident {
+                            Some(id) => id.unraw() == member,
+                            None => j.to_string() == member,
+                        })
+                        .ok_or_else(|| {
+                            syn::Error::new(
+                                template::subspan(&ls, p.arg_range.clone()),
+                                format!("there is no field `{}`", member),
+                            )
+                        })?,
+                    None => return Err(syn::Error::new(
+                        template::subspan(&ls, p.arg_range.clone()),
+                        "expected `{}` for the value of the field or `{self.name}` for any field",
+                    )),
+                };
+            let name = if j == i {
+                format_ident!("__value")
+            } else {
+                field_binding(j, accesses[j].field)
+            };
+            if !args.iter().any(|(arg, _)| *arg == name) {
+                // The place of the field rather than a reference to it, which
+                // `format_args!` borrows itself, so that `{:p}` prints the
+                // pointer held by the field and not the field's own address.
+                let access = &accesses[j].access;
+                args.push((name.clone(), quote! { *#access }));
+            }
+            names.push(name.to_string());
+            if accesses[i].attr.bound.is_none() {
+                bounded.push((p.fmt_trait(), accesses[j].field));
             }
         }
-        return Some(Err(syn::Error::new(
-            meta.span(),
-            r#"expected `debug = "..."`"#,
-        )));
-    }
-    None
-}
-
-type FieldList = syn::punctuated::Punctuated<syn::Field, syn::token::Comma>;
-
-fn join_path_segment(
-    pss: &syn::punctuated::Punctuated<syn::PathSegment, syn::token::Colon2>,
-) -> String {
-    let list: Vec<_> = pss.iter().map(|ps| ps.ident.to_string()).collect();
-    list.join("::")
-}
-
-fn get_ty_bound(id: &syn::Ident, ty: &syn::Type) -> Option<String> {
-    if let syn::Type::Path(syn::TypePath {
-        path: syn::Path { segments, .. },
-        ..
-    }) = ty
-    {
-        let f = segments.first()?;
-        if f.ident.to_string() == id.to_string() {
-            return Some(join_path_segment(segments));
-        }
-        let las = segments.last()?;
-        if las.ident != "PhantomData" {
-            if let syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments {
-                args,
-                ..
-            }) = &las.arguments
-            {
-                for arg in args {
-                    if let syn::GenericArgument::Type(ty) = arg {
-                        if let Some(s) = get_ty_bound(id, ty) {
-                            return Some(s);
-                        }
+        let mut names = names.into_iter();
+        let ls = template::rename_args(&ls, &placeholders, |_| names.next().unwrap());
+        let (arg_names, arg_values): (Vec<_>, Vec<_>) = args.into_iter().unzip();
+        let formatted = quote! { &::core::format_args!(#ls, #(#arg_names = #arg_values),*) };
+        accesses[i].formatted = Some(formatted);
+    }
+    Ok(bounded)
+}
+
+// The value printed for a field, as a `&dyn Debug`.
+fn field_value(fa: &FieldAccess) -> TS2 {
+    let access = &fa.access;
+    let attr = &fa.attr;
+    if let Some(placeholder) = &attr.redact {
+        quote! { &::core::format_args!("{}", #placeholder) }
+    } else if let Some(path) = &attr.with {
+        quote! { &DebugWith(|fmt: &mut ::core::fmt::Formatter| #path(#access, fmt)) }
+    } else if let Some(formatted) = &fa.formatted {
+        formatted.clone()
+    } else if let Some(radix) = attr.radix {
+        let spec = radix.spec();
+        let prefix = radix.prefix();
+        let digit_bits = radix.digit_bits();
+        let group = radix.group();
+        // The width of the integer behind any references, not of a pointer.
+        let derefs = (0..reference_depth(&fa.field.ty)).map(|_| quote! { * });
+        quote! {
+            &DebugRadix {
+                digits: |buf: &mut DebugRadixBuf| {
+                    ::core::fmt::Write::write_fmt(buf, ::core::format_args!(#spec, #access))
+                },
+                bits: ::core::mem::size_of_val(#(#derefs)* #access) * 8,
+                prefix: #prefix,
+                digit_bits: #digit_bits,
+                group: #group,
+            }
+        }
+    } else if let Some(max_len) = attr.max_len {
+        if is_string(&fa.field.ty) {
+            quote! { &DebugTruncatedStr(&(#access)[..], #max_len) }
+        } else {
+            quote! { &DebugTruncatedList((#access).iter(), #max_len) }
+        }
+    } else {
+        quote! { #access }
+    }
+}
+
+// Whether the container `#[debug(hex)]` and friends apply to a field of type
+// `ty`, a primitive integer.
+fn is_integer(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => [
+            "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+        ]
+        .iter()
+        .any(|name| path.is_ident(name)),
+        _ => false,
+    }
+}
+
+// How many references `ty` is behind, 1 for `&'a u8`.
+fn reference_depth(ty: &syn::Type) -> usize {
+    match ty {
+        syn::Type::Reference(reference) => 1 + reference_depth(&reference.elem),
+        _ => 0,
+    }
+}
+
+// Whether `#[debug(max_len = ...)]` truncates a field of type `ty` by
+// characters rather than by elements.
+fn is_string(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Reference(reference) => is_string(&reference.elem),
+        syn::Type::Path(syn::TypePath { qself: None, path }) => match path.segments.last() {
+            Some(last) => last.ident == "str" || last.ident == "String",
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+// Whether the container `#[debug(max_len = ...)]` applies to a field of type
+// `ty`, a string or a collection with an `iter()`.
+fn is_truncatable(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Reference(reference) => is_truncatable(&reference.elem),
+        syn::Type::Array(_) | syn::Type::Slice(_) => true,
+        syn::Type::Path(syn::TypePath { qself: None, path }) => match path.segments.last() {
+            Some(last) => ["str", "String", "Vec", "VecDeque"]
+                .iter()
+                .any(|name| last.ident == name),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+// Gives the container `#[debug(max_len = ...)]` to the strings and
+// collections, and the container radix to the integers, that would otherwise
+// be printed through `Debug`.
+fn container_defaults(accesses: &mut [FieldAccess], container: &ContainerAttr) {
+    for fa in accesses.iter_mut() {
+        if !fa.attr.needs_debug() || fa.attr.max_len.is_some() {
+            continue;
+        }
+        if is_truncatable(&fa.field.ty) {
+            fa.attr.max_len = container.max_len;
+        } else if is_integer(&fa.field.ty) {
+            fa.attr.radix = container.radix;
+        }
+    }
+}
+
+// Adds the named fields to the `DebugStruct` in `__debug`, leaving whether
+// anything was left out in `__non_exhaustive`. A `#[debug(flatten)]` field
+// adds its own fields through the `__debug_fields` method that the derive
+// generates for the structs marked `#[debug(flattenable)]`; the call is
+// spanned to the field's type, where a missing method is reported.
+//
+// `rename_all` is the rule applied to the names of fields without their own
+// `#[debug(rename = "...")]`, and `skip_none` leaves out the `None` and empty
+// `Vec` fields without a `#[debug(skip_if = "...")]`.
+fn named_fields(accesses: &[FieldAccess], rename_all: Option<RenameRule>, skip_none: bool) -> TS2 {
+    let skipped = accesses.iter().any(|fa| fa.attr.skip);
+    let mutability = if accesses.iter().any(|fa| fa.attr.flatten) {
+        quote! { mut }
+    } else {
+        TS2::new()
+    };
+    let stmts = accesses
+        .iter()
+        .enumerate()
+        .filter(|(_, fa)| !fa.attr.skip)
+        .map(|(i, fa)| {
+            let access = &fa.access;
+            if fa.attr.flatten {
+                let ty = &fa.field.ty;
+                return quote_spanned! {ty.span()=>
+                    __non_exhaustive |= (#access).__debug_fields(__debug);
+                };
+            }
+            let sid = field_name(i, fa, rename_all);
+            let value = field_value(fa);
+            match skip_check(fa, skip_none) {
+                Some(skip_if) => quote! {
+                    if !#skip_if(#access) {
+                        __debug.field(#sid, #value);
                     }
-                }
+                },
+                None => quote! { __debug.field(#sid, #value); },
+            }
+        });
+    quote! {
+        let #mutability __non_exhaustive = #skipped;
+        #(#stmts)*
+    }
+}
+
+// The printed name of a named field: its `#[debug(rename = "...")]`, or its
+// name converted by `rename_all`. Tuple fields are named by their index `i`.
+fn field_name(i: usize, fa: &FieldAccess, rename_all: Option<RenameRule>) -> String {
+    let id = match &fa.field.ident {
+        Some(id) => id,
+        None => return i.to_string(),
+    };
+    match (&fa.attr.rename, rename_all) {
+        (Some(rename), _) => rename.clone(),
+        (None, Some(rule)) => rule.apply(&id.unraw().to_string()),
+        (None, None) => format!("{}", id),
+    }
+}
+
+// The function deciding at runtime whether a field is left out, from
+// `#[debug(skip_if = "...")]` or the container `#[debug(skip_none)]`.
+fn skip_check(fa: &FieldAccess, skip_none: bool) -> Option<TS2> {
+    match &fa.attr.skip_if {
+        Some(path) => Some(quote! { #path }),
+        None if skip_none => empty_check(&fa.field.ty),
+        None => None,
+    }
+}
+
+// How `#[debug(skip_none)]` tells that a field of type `ty` is empty, if it
+// is an `Option` or a `Vec`.
+fn empty_check(ty: &syn::Type) -> Option<TS2> {
+    let last = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last()?,
+        _ => return None,
+    };
+    if !matches!(last.arguments, syn::PathArguments::AngleBracketed(_)) {
+        return None;
+    }
+    if last.ident == "Option" {
+        Some(quote! { ::core::option::Option::is_none })
+    } else if last.ident == "Vec" {
+        Some(quote! { <[_]>::is_empty })
+    } else {
+        None
+    }
+}
+
+fn finish_named() -> TS2 {
+    quote! {
+        if __non_exhaustive {
+            __debug.finish_non_exhaustive()
+        } else {
+            __debug.finish()
+        }
+    }
+}
+
+fn debug_fields(
+    name: &str,
+    fields: &syn::Fields,
+    accesses: &[FieldAccess],
+    rename_all: Option<RenameRule>,
+    skip_none: bool,
+) -> TS2 {
+    match fields {
+        syn::Fields::Named(_) => {
+            let named_fields = named_fields(accesses, rename_all, skip_none);
+            let finish = finish_named();
+            quote! {{
+                let __debug = &mut fmt.debug_struct(#name);
+                #named_fields
+                #finish
+            }}
+        }
+        syn::Fields::Unnamed(_) => {
+            let debug_fields = accesses.iter().filter(|fa| !fa.attr.skip).map(|fa| {
+                let value = field_value(fa);
+                quote! { .field(#value) }
+            });
+            let finish = if accesses.iter().any(|fa| fa.attr.skip) {
+                quote! { .finish_non_exhaustive() }
+            } else {
+                quote! { .finish() }
+            };
+            quote! {
+                fmt.debug_tuple(#name)
+                   #(#debug_fields)*
+                   #finish
             }
         }
+        syn::Fields::Unit => quote! { fmt.write_str(#name) },
     }
-    None
 }
 
-fn get_tp_trait_bound(tp: &syn::TypeParam, fields: &FieldList) -> Vec<syn::WherePredicate> {
-    let mut bounds = Vec::new();
-    for f in fields.iter() {
-        let ty = &f.ty;
-        if let Some(s) = get_ty_bound(&tp.ident, ty) {
-            let ts: TS2 = s.parse().unwrap();
-            bounds.push(parse_quote!(#ts: std::fmt::Debug));
+// `#[debug(transparent)]`: the one field of a newtype printed on its own.
+fn debug_transparent(ast: &DeriveInput, accesses: &[FieldAccess]) -> Result<TS2> {
+    match accesses {
+        [fa] if !fa.attr.skip && fa.attr.skip_if.is_none() && !fa.attr.flatten => {
+            let value = field_value(fa);
+            Ok(quote! { ::core::fmt::Debug::fmt(#value, fmt) })
         }
+        _ => Err(syn::Error::new(
+            ast.ident.span(),
+            "`debug(transparent)` needs a struct with exactly one printed field",
+        )),
     }
-    bounds
 }
 
-fn add_bounds_to_generics(g: &syn::Generics, fields: &FieldList) -> syn::Generics {
-    let mut mg = g.clone();
-    let where_clause = mg.make_where_clause();
-    for p in &g.params {
-        if let syn::GenericParam::Type(tp) = p {
-            for b in get_tp_trait_bound(tp, fields).into_iter() {
-                where_clause.predicates.push(b);
+// Adapts a `#[debug(with = "...")]` function to `Debug`; only emitted into
+// `fmt` when some field uses it.
+fn debug_with_helper() -> TS2 {
+    quote! {
+        struct DebugWith<F>(F);
+
+        impl<F> ::core::fmt::Debug for DebugWith<F>
+        where
+            F: ::core::ops::Fn(&mut ::core::fmt::Formatter) -> ::core::fmt::Result,
+        {
+            fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                (self.0)(fmt)
             }
         }
     }
-    mg
 }
 
-fn add_bounds_to_generics_from_attr(g: &syn::Generics, attr: &syn::Attribute) -> Result<syn::Generics> {
-    let mut mg = g.clone();
-    let where_clause = mg.make_where_clause();
-    let meta = match attr.parse_meta() {
-        Ok(meta) => meta,
-        Err(e) => return Err(e),
-    };
-    if let syn::Meta::List(list) = &meta {
-        if list.nested.len() == 1 {
-            let a = list.nested.first().unwrap();
-            if let syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) = a {
-                if nv.path.get_ident().unwrap() == "bound" {
-                    if let syn::Lit::Str(ls) = &nv.lit {
-                        let ts: TS2 = ls.value().parse()?;
-                        where_clause.predicates.push(parse_quote!(#ts));
-                        return Ok(mg);
+// Prints at most `max_len` elements of a collection, or characters of a
+// string, for `#[debug(max_len = ...)]`; only emitted into `fmt` when some
+// field uses it. Collections are read through their `iter()`, so nothing
+// here depends on `alloc`.
+fn debug_truncated_helper() -> TS2 {
+    quote! {
+        struct DebugTruncatedStr<'a>(&'a str, usize);
+
+        impl ::core::fmt::Debug for DebugTruncatedStr<'_> {
+            fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                match self.0.char_indices().nth(self.1) {
+                    ::core::option::Option::Some((end, _)) => {
+                        ::core::fmt::Debug::fmt(&self.0[..end], fmt)?;
+                        let more = self.0[end..].chars().count();
+                        ::core::write!(fmt, "\u{2026} ({} more)", more)
                     }
+                    ::core::option::Option::None => ::core::fmt::Debug::fmt(self.0, fmt),
+                }
+            }
+        }
+
+        struct DebugTruncatedList<I>(I, usize);
+
+        impl<I> ::core::fmt::Debug for DebugTruncatedList<I>
+        where
+            I: ::core::clone::Clone + ::core::iter::ExactSizeIterator,
+            I::Item: ::core::fmt::Debug,
+        {
+            fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                let len = self.0.len();
+                let mut list = fmt.debug_list();
+                list.entries(self.0.clone().take(self.1));
+                if len > self.1 {
+                    list.entry(&::core::format_args!("\u{2026} ({} more)", len - self.1));
                 }
+                list.finish()
+            }
+        }
+    }
+}
+
+// Prints the raw bytes of an `unsafe_bytes` union as `[0x01, 0xff]`.
+fn debug_bytes_helper() -> TS2 {
+    quote! {
+        struct DebugBytes<'a>(&'a [u8]);
+
+        struct DebugByte(u8);
+
+        impl ::core::fmt::Debug for DebugByte {
+            fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                ::core::write!(fmt, "{:#04x}", self.0)
+            }
+        }
+
+        impl ::core::fmt::Debug for DebugBytes<'_> {
+            fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                fmt.debug_list()
+                    .entries(self.0.iter().map(|b| DebugByte(*b)))
+                    .finish()
             }
         }
     }
-    Err(syn::Error::new(
-        meta.span(),
-        r#"expected `debug(bound = "...")`"#,
-    ))
+}
+
+// Prints the digits of an integer for `#[debug(hex)]` and friends, without
+// allocating; only emitted into `fmt` when some field uses it.
+fn debug_radix_helper() -> TS2 {
+    quote! {
+        // Room for the 128 binary digits of a `u128`.
+        struct DebugRadixBuf {
+            bytes: [u8; 128],
+            len: usize,
+        }
+
+        impl ::core::fmt::Write for DebugRadixBuf {
+            fn write_str(&mut self, s: &str) -> ::core::fmt::Result {
+                let end = self.len + s.len();
+                self.bytes
+                    .get_mut(self.len..end)
+                    .ok_or(::core::fmt::Error)?
+                    .copy_from_slice(s.as_bytes());
+                self.len = end;
+                ::core::result::Result::Ok(())
+            }
+        }
+
+        struct DebugRadix<F> {
+            digits: F,
+            bits: usize,
+            prefix: &'static str,
+            digit_bits: usize,
+            group: usize,
+        }
+
+        impl<F> ::core::fmt::Debug for DebugRadix<F>
+        where
+            F: ::core::ops::Fn(&mut DebugRadixBuf) -> ::core::fmt::Result,
+        {
+            fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                let mut buf = DebugRadixBuf {
+                    bytes: [0; 128],
+                    len: 0,
+                };
+                (self.digits)(&mut buf)?;
+                let digits = &buf.bytes[..buf.len];
+                fmt.write_str(self.prefix)?;
+                if !fmt.alternate() {
+                    for &d in digits {
+                        ::core::fmt::Write::write_char(fmt, d as char)?;
+                    }
+                    return ::core::result::Result::Ok(());
+                }
+                let width = self.bits.div_ceil(self.digit_bits);
+                let len = ::core::cmp::max(width, digits.len());
+                let padding = len - digits.len();
+                for i in 0..len {
+                    if i > 0 && (len - i) % self.group == 0 {
+                        ::core::fmt::Write::write_char(fmt, '_')?;
+                    }
+                    let d = if i < padding { b'0' } else { digits[i - padding] };
+                    ::core::fmt::Write::write_char(fmt, d as char)?;
+                }
+                ::core::result::Result::Ok(())
+            }
+        }
+    }
+}
+
+fn debug_generics(
+    ast: &DeriveInput,
+    container: &ContainerAttr,
+    fields: &[&Field],
+    fmt_bounds: &[(&'static str, &Field)],
+    bounds: &[syn::WherePredicate],
+) -> syn::Generics {
+    let g = match &container.bound {
+        Some(container_bounds) => add_bounds_to_generics_from_attr(&ast.generics, container_bounds),
+        None => add_fmt_bounds_to_generics(
+            &add_bounds_to_generics(
+                &ast.generics,
+                fields,
+                &container.phantom,
+                &parse_quote!(::core::fmt::Debug),
+            ),
+            fmt_bounds,
+            &container.phantom,
+        ),
+    };
+    add_bounds_to_generics_from_attr(&g, bounds)
 }
 
 fn expand(ast: &DeriveInput) -> Result<TS2> {
+    check_attrs(ast)?;
     let name = &ast.ident;
-    let sname = format!("{}", name);
-    let fields = if let syn::Data::Struct(syn::DataStruct {
-        fields: syn::Fields::Named(syn::FieldsNamed { named, .. }),
-        ..
-    }) = &ast.data
-    {
-        named
-    } else {
-        return Err(syn::Error::new(ast.span(), "Not a name struct"));
-    };
-    let g = if ast.attrs.len() > 0 {
-        add_bounds_to_generics_from_attr(&ast.generics, ast.attrs.first().unwrap())?
-    } else {
-        add_bounds_to_generics(&ast.generics, fields)
+    let container = get_container_attr(&ast.attrs)?;
+    let sname = match (&container.rename, &ast.data) {
+        (Some(_), syn::Data::Enum(_)) => {
+            return Err(syn::Error::new(
+                ast.ident.span(),
+                "the name of an enum is not printed, rename its variants instead",
+            ))
+        }
+        (Some(rename), _) => rename.clone(),
+        (None, _) => format!("{}", name),
     };
-    let (impl_generics, ty_generics, where_clause) = g.split_for_impl();
-    let debug_fields = fields.iter().map(|f| {
-        let id = &f.ident;
-        let sid = format!("{}", id.as_ref().unwrap());
-        match get_debug_attr(f) {
-            Some(Ok(s)) => {
-                quote! {
-                    .field(#sid, &format_args!(#s, self.#id))
+    if (container.opaque || container.with.is_some()) && !matches!(ast.data, syn::Data::Union(_)) {
+        return Err(syn::Error::new(
+            ast.ident.span(),
+            "`debug(opaque)` and `debug(with = \"...\")` are only for unions",
+        ));
+    }
+    if container.unsafe_bytes && !container.opaque {
+        return Err(syn::Error::new(
+            ast.ident.span(),
+            "`debug(unsafe_bytes)` only goes with `debug(opaque)`",
+        ));
+    }
+    let named_struct = matches!(
+        &ast.data,
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(_),
+            ..
+        })
+    );
+    if container.flattenable && (!named_struct || container.transparent) {
+        return Err(syn::Error::new(
+            ast.ident.span(),
+            "`debug(flattenable)` needs a struct with named fields that is not `transparent`",
+        ));
+    }
+    if container.transparent && !matches!(ast.data, syn::Data::Struct(_)) {
+        return Err(syn::Error::new(
+            ast.ident.span(),
+            "`debug(transparent)` needs a struct with exactly one printed field",
+        ));
+    }
+    let mut uses_with = false;
+    let mut uses_max_len = false;
+    let mut uses_radix = false;
+    let mut uses_bytes = false;
+    let mut bounds = Vec::new();
+    let mut fmt_bounds = Vec::new();
+    // `__debug_fields`, for the `flattenable` structs
+    let mut fields_fn = None;
+    let (fields, body): (Vec<&Field>, TS2) = match &ast.data {
+        syn::Data::Struct(data) => {
+            let mut accesses = field_accesses_of_self(&data.fields)?;
+            container_defaults(&mut accesses, &container);
+            fmt_bounds.extend(resolve_formats(&mut accesses)?);
+            uses_with |= accesses.iter().any(|fa| fa.attr.with.is_some());
+            uses_max_len |= accesses.iter().any(|fa| fa.attr.max_len.is_some());
+            uses_radix |= accesses.iter().any(|fa| fa.attr.radix.is_some());
+            bounds.extend(field_bounds(&accesses));
+            let body = match &data.fields {
+                _ if container.transparent => debug_transparent(ast, &accesses)?,
+                syn::Fields::Named(_) if container.flattenable => {
+                    fields_fn = Some(named_fields(
+                        &accesses,
+                        container.rename_all,
+                        container.skip_none,
+                    ));
+                    quote! {
+                        let __debug = &mut fmt.debug_struct(#sname);
+                        let __non_exhaustive = self.__debug_fields(__debug);
+                    }
                 }
+                fields => debug_fields(
+                    &sname,
+                    fields,
+                    &accesses,
+                    container.rename_all,
+                    container.skip_none,
+                ),
+            };
+            (bound_fields(&accesses), body)
+        }
+        syn::Data::Enum(data) if data.variants.is_empty() => {
+            (Vec::new(), quote! { match *self {} })
+        }
+        syn::Data::Enum(data) => {
+            let mut fields = Vec::new();
+            let mut arms = Vec::new();
+            for v in data.variants.iter() {
+                let vid = &v.ident;
+                let vname = match (get_variant_attr(v)?.rename, container.rename_all) {
+                    (Some(rename), _) => rename,
+                    (None, Some(rule)) => rule.apply(&vid.unraw().to_string()),
+                    (None, None) => format!("{}", vid),
+                };
+                let pattern = variant_pattern(&v.fields);
+                let mut accesses = field_accesses_of_binding(&v.fields)?;
+                container_defaults(&mut accesses, &container);
+                fmt_bounds.extend(resolve_formats(&mut accesses)?);
+                uses_with |= accesses.iter().any(|fa| fa.attr.with.is_some());
+                uses_max_len |= accesses.iter().any(|fa| fa.attr.max_len.is_some());
+                uses_radix |= accesses.iter().any(|fa| fa.attr.radix.is_some());
+                bounds.extend(field_bounds(&accesses));
+                let body = debug_fields(&vname, &v.fields, &accesses, None, container.skip_none);
+                fields.extend(bound_fields(&accesses));
+                arms.push(quote! { Self::#vid #pattern => #body, });
             }
-            // Not process error
-            _ => {
+            (
+                fields,
                 quote! {
-                    .field(#sid, &self.#id)
-                }
+                    match self {
+                        #(#arms)*
+                    }
+                },
+            )
+        }
+        syn::Data::Union(_) => match (&container.with, container.opaque) {
+            (Some(path), false) => (Vec::new(), quote! { #path(self, fmt) }),
+            (None, true) if container.unsafe_bytes => {
+                uses_bytes = true;
+                (
+                    Vec::new(),
+                    quote! {
+                        // Safety: every byte of the union is initialized, as its
+                        // author vouched with `unsafe_bytes`.
+                        let bytes = unsafe {
+                            ::core::slice::from_raw_parts(
+                                self as *const Self as *const u8,
+                                ::core::mem::size_of::<Self>(),
+                            )
+                        };
+                        fmt.debug_tuple(#sname).field(&DebugBytes(bytes)).finish()
+                    },
+                )
+            }
+            // Nothing is read from the union, whose bytes may be partly
+            // uninitialized.
+            (None, true) => (
+                Vec::new(),
+                quote! { fmt.debug_struct(#sname).finish_non_exhaustive() },
+            ),
+            _ => {
+                return Err(syn::Error::new(
+                    ast.ident.span(),
+                    r#"the fields of a union cannot be printed, use `#[debug(opaque)]` to print only its name or `#[debug(with = "...")]`"#,
+                ))
             }
+        },
+    };
+    let g = debug_generics(ast, &container, &fields, &fmt_bounds, &bounds);
+    let (impl_generics, ty_generics, where_clause) = g.split_for_impl();
+    let mut helper = TS2::new();
+    if uses_with {
+        helper.extend(debug_with_helper());
+    }
+    if uses_max_len {
+        helper.extend(debug_truncated_helper());
+    }
+    if uses_radix {
+        helper.extend(debug_radix_helper());
+    }
+    if uses_bytes {
+        helper.extend(debug_bytes_helper());
+    }
+    let (helper, body, inherent) = match fields_fn {
+        Some(named_fields) => {
+            let finish = finish_named();
+            (
+                TS2::new(),
+                quote! { #body #finish },
+                quote! {
+                    impl #impl_generics #name #ty_generics #where_clause {
+                        #[doc(hidden)]
+                        pub fn __debug_fields(
+                            &self,
+                            __debug: &mut ::core::fmt::DebugStruct<'_, '_>,
+                        ) -> bool {
+                            #helper
+                            #named_fields
+                            __non_exhaustive
+                        }
+                    }
+                },
+            )
         }
-    });
+        None => (helper, body, TS2::new()),
+    };
     Ok(quote! {
-        impl #impl_generics std::fmt::Debug for #name #ty_generics #where_clause {
-            fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
-                fmt.debug_struct(#sname)
-                   #(#debug_fields)*
-                   .finish()
+        impl #impl_generics ::core::fmt::Debug for #name #ty_generics #where_clause {
+            fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #helper
+                #body
             }
         }
+
+        #inherent
     })
 }
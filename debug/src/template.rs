@@ -0,0 +1,131 @@
+//! Parsing of the `format_args!` style templates given in attributes, so that
+//! the placeholders can be checked and resolved against the fields before the
+//! template reaches the generated code.
+
+use proc_macro2::Span;
+use syn::Result;
+
+pub struct Placeholder {
+    // The argument before the `:`, empty for the next positional argument
+    pub arg: String,
+    // Everything after the `:`
+    pub spec: String,
+    // Byte range of the argument inside the template
+    pub arg_range: std::ops::Range<usize>,
+}
+
+impl Placeholder {
+    // The formatting trait selected by the spec, e.g. `LowerHex` for `{:#x}`.
+    pub fn fmt_trait(&self) -> &'static str {
+        let ty = self
+            .spec
+            .trim_end_matches(|c: char| c == '?' || c.is_ascii_alphabetic());
+        match &self.spec[ty.len()..] {
+            "" => "Display",
+            "?" | "x?" | "X?" => "Debug",
+            "x" => "LowerHex",
+            "X" => "UpperHex",
+            "o" => "Octal",
+            "b" => "Binary",
+            "e" => "LowerExp",
+            "E" => "UpperExp",
+            "p" => "Pointer",
+            _ => "Display",
+        }
+    }
+}
+
+// The span of `range` inside the template, when the compiler can point into
+// the literal and the literal has no escapes; the whole literal otherwise.
+pub fn subspan(ls: &syn::LitStr, range: std::ops::Range<usize>) -> Span {
+    let token = ls.token();
+    let text = token.to_string();
+    let value = ls.value();
+    let start = match text.find('"') {
+        Some(quote) => quote + 1,
+        None => return ls.span(),
+    };
+    if text.get(start..start + value.len()) != Some(value.as_str()) {
+        return ls.span();
+    }
+    token
+        .subspan(start + range.start..start + range.end)
+        .unwrap_or_else(|| ls.span())
+}
+
+fn error(ls: &syn::LitStr, range: std::ops::Range<usize>, msg: &str) -> syn::Error {
+    syn::Error::new(
+        subspan(ls, range),
+        format!("invalid format string: {}", msg),
+    )
+}
+
+// Width and precision taken from arguments, `{:1$}` or `{:.*}`, would refer
+// to arguments that the generated code does not pass.
+pub fn check_spec(ls: &syn::LitStr, p: &Placeholder) -> Result<()> {
+    if p.spec.contains('$') || p.spec.contains(".*") {
+        return Err(syn::Error::new(
+            subspan(ls, p.arg_range.end..p.arg_range.end + p.spec.len() + 1),
+            "width and precision cannot be taken from arguments, write them out",
+        ));
+    }
+    Ok(())
+}
+
+pub fn placeholders(ls: &syn::LitStr) -> Result<Vec<Placeholder>> {
+    let s = ls.value();
+    let mut placeholders = Vec::new();
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '{' if matches!(chars.peek(), Some((_, '{'))) => {
+                chars.next();
+            }
+            '}' if matches!(chars.peek(), Some((_, '}'))) => {
+                chars.next();
+            }
+            '{' => {
+                let start = i + 1;
+                let end = loop {
+                    match chars.next() {
+                        Some((j, '}')) => break j,
+                        Some((_, '{')) | None => return Err(error(ls, i..i + 1, "unmatched `{`")),
+                        Some(_) => (),
+                    }
+                };
+                let inner = &s[start..end];
+                let (arg, spec) = match inner.find(':') {
+                    Some(colon) => (&inner[..colon], &inner[colon + 1..]),
+                    None => (inner, ""),
+                };
+                let arg_start = start + (arg.len() - arg.trim_start().len());
+                placeholders.push(Placeholder {
+                    arg: arg.trim().to_owned(),
+                    spec: spec.to_owned(),
+                    arg_range: arg_start..arg_start + arg.trim().len(),
+                });
+            }
+            '}' => return Err(error(ls, i..i + 1, "unmatched `}`")),
+            _ => (),
+        }
+    }
+    Ok(placeholders)
+}
+
+// Rebuilds the template with every argument replaced by `rename(arg)`.
+pub fn rename_args(
+    ls: &syn::LitStr,
+    placeholders: &[Placeholder],
+    mut rename: impl FnMut(&Placeholder) -> String,
+) -> syn::LitStr {
+    let s = ls.value();
+    let mut out = String::with_capacity(s.len());
+    let mut last = 0;
+    for p in placeholders {
+        out.push_str(&s[last..p.arg_range.start]);
+        out.push_str(&rename(p));
+        last = p.arg_range.end;
+    }
+    out.push_str(&s[last..]);
+    syn::LitStr::new(&out, ls.span())
+}
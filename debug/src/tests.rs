@@ -0,0 +1,143 @@
+// Snapshots of the impls generated by CustomDebug, pretty printed next to the
+// input they were derived from. They make every change to the generated code
+// visible in review, including the ones that do not change the output.
+//
+// The expected expansions are stored in tests/snapshots/expand and are updated
+// by running the tests with SNAPSHOTS=overwrite, like the rendering snapshots
+// in tests/snapshots.rs.
+
+#[path = "../tests/snapshot/mod.rs"]
+mod snapshot;
+
+use proc_macro2::TokenStream as TS2;
+use quote::quote;
+
+fn expand_snapshot(name: &str, input: TS2) {
+    let ast = syn::parse2(input.clone()).unwrap();
+    let expanded = crate::expand(&ast).unwrap();
+    let file = syn::parse2(quote!(#input #expanded)).unwrap();
+    snapshot::assert_snapshot(
+        &format!("tests/snapshots/expand/{}.expanded.rs", name),
+        &prettyplease::unparse(&file),
+    );
+}
+
+#[test]
+fn expansions() {
+    expand_snapshot(
+        "struct",
+        quote! {
+            pub struct Field {
+                name: &'static str,
+                #[debug = "0b{:08b}"]
+                bitmask: u8,
+            }
+        },
+    );
+    expand_snapshot(
+        "tuple",
+        quote! {
+            pub struct Pair(u8, #[debug(skip)] u8);
+        },
+    );
+    expand_snapshot(
+        "enum",
+        quote! {
+            #[debug(rename_all = "snake_case")]
+            pub enum Shape {
+                Empty,
+                Circle { radius: f32 },
+                Rect(u32, #[debug(redact)] u32),
+            }
+        },
+    );
+    expand_snapshot(
+        "generic",
+        quote! {
+            pub struct Generic<T: Trait, M> {
+                value: T,
+                assoc: Vec<T::Value>,
+                marker: PhantomData<M>,
+            }
+        },
+    );
+    expand_snapshot(
+        "bound",
+        quote! {
+            #[debug(bound = "T::Value: Debug")]
+            pub struct Wrapper<T: Trait> {
+                values: Vec<T::Value>,
+            }
+        },
+    );
+    expand_snapshot(
+        "flatten",
+        quote! {
+            pub struct Outer {
+                #[debug(flatten)]
+                inner: Inner,
+                #[debug(skip_if = "Option::is_none")]
+                extra: Option<u8>,
+            }
+        },
+    );
+    expand_snapshot(
+        "flattenable",
+        quote! {
+            #[debug(flattenable)]
+            pub struct Inner {
+                a: u8,
+                #[debug(skip)]
+                b: u8,
+            }
+        },
+    );
+    expand_snapshot(
+        "transparent",
+        quote! {
+            #[debug(transparent)]
+            pub struct Id(u64);
+        },
+    );
+    expand_snapshot(
+        "with-and-max-len",
+        quote! {
+            pub struct Buffer {
+                #[debug(with = "hidden")]
+                token: u32,
+                #[debug(max_len = 8)]
+                bytes: Vec<u8>,
+            }
+        },
+    );
+    expand_snapshot(
+        "radix",
+        quote! {
+            #[debug(hex)]
+            pub struct Header {
+                kind: u16,
+                flags: u8,
+            }
+        },
+    );
+    expand_snapshot(
+        "union",
+        quote! {
+            #[debug(opaque)]
+            pub union Bits {
+                int: u32,
+                float: f32,
+            }
+        },
+    );
+    expand_snapshot(
+        "union-bytes",
+        quote! {
+            #[debug(opaque, unsafe_bytes)]
+            pub union Raw {
+                int: u32,
+                bytes: [u8; 4],
+            }
+        },
+    );
+}
@@ -0,0 +1,115 @@
+//! `#[derive(CustomValuable)]`: a `visit_fields` method handing every field to
+//! a visitor as a name and a `&dyn Debug`, for structured logging.
+//!
+//! The fields are printed the same way as by `CustomDebug`, following the
+//! same `#[debug(...)]` attributes. The visitor is any implementation of the
+//! trait named by `#[valuable(visitor = "...")]`, or of the `FieldVisitor` in
+//! scope otherwise, which has to provide a
+//! `fn visit_field(&mut self, name: &str, value: &dyn ::core::fmt::Debug)`.
+
+use crate::{
+    attr::{check_attrs, get_container_attr},
+    bound_fields, container_defaults, debug_generics, debug_radix_helper, debug_truncated_helper,
+    debug_with_helper, field_accesses_of_self, field_bounds, field_name, field_value,
+    resolve_formats, skip_check,
+};
+use proc_macro2::{Span, TokenStream as TS2};
+use quote::quote;
+use syn::{DeriveInput, Result};
+
+// The path of `#[valuable(visitor = "...")]`, `FieldVisitor` by default.
+fn get_visitor(attrs: &[syn::Attribute]) -> Result<syn::Path> {
+    let mut visitor = None;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("valuable")) {
+        let meta = attr.parse_meta()?;
+        match &meta {
+            syn::Meta::List(list) if list.nested.len() == 1 => match list.nested.first() {
+                Some(syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Str(ls),
+                    ..
+                }))) if path.is_ident("visitor") && visitor.is_none() => {
+                    visitor = Some(ls.parse()?)
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        r#"expected `valuable(visitor = "...")`"#,
+                    ))
+                }
+            },
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    r#"expected `valuable(visitor = "...")`"#,
+                ))
+            }
+        }
+    }
+    Ok(visitor.unwrap_or_else(|| syn::parse_quote!(FieldVisitor)))
+}
+
+pub fn expand(ast: &DeriveInput) -> Result<TS2> {
+    check_attrs(ast)?;
+    let name = &ast.ident;
+    let container = get_container_attr(&ast.attrs)?;
+    let visitor = get_visitor(&ast.attrs)?;
+    let data = match &ast.data {
+        syn::Data::Struct(data) if !container.transparent => data,
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`CustomValuable` is only derived for structs that are not transparent",
+            ))
+        }
+    };
+    let mut accesses = field_accesses_of_self(&data.fields)?;
+    container_defaults(&mut accesses, &container);
+    let fmt_bounds = resolve_formats(&mut accesses)?;
+    let visits = accesses
+        .iter()
+        .enumerate()
+        .filter(|(_, fa)| !fa.attr.skip)
+        .map(|(i, fa)| {
+            let access = &fa.access;
+            if fa.attr.flatten {
+                return quote! { (#access).visit_fields(visitor); };
+            }
+            let sid = field_name(i, fa, container.rename_all);
+            let value = field_value(fa);
+            match skip_check(fa, container.skip_none) {
+                Some(skip_if) => quote! {
+                    if !#skip_if(#access) {
+                        visitor.visit_field(#sid, #value);
+                    }
+                },
+                None => quote! { visitor.visit_field(#sid, #value); },
+            }
+        });
+    let mut helper = TS2::new();
+    if accesses.iter().any(|fa| fa.attr.with.is_some()) {
+        helper.extend(debug_with_helper());
+    }
+    if accesses.iter().any(|fa| fa.attr.max_len.is_some()) {
+        helper.extend(debug_truncated_helper());
+    }
+    if accesses.iter().any(|fa| fa.attr.radix.is_some()) {
+        helper.extend(debug_radix_helper());
+    }
+    let g = debug_generics(
+        ast,
+        &container,
+        &bound_fields(&accesses),
+        &fmt_bounds,
+        &field_bounds(&accesses),
+    );
+    let (impl_generics, ty_generics, where_clause) = g.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub fn visit_fields(&self, visitor: &mut impl #visitor) {
+                #helper
+                #(#visits)*
+            }
+        }
+    })
+}
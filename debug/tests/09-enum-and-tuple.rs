@@ -0,0 +1,62 @@
+// Tuple structs, unit structs and enums are derived the same way the standard
+// library's #[derive(Debug)] would print them: tuple structs and tuple variants
+// through DebugTuple, struct variants through DebugStruct, and unit structs and
+// unit variants as just their name.
+//
+// The #[debug = "..."] format override keeps working on every kind of field,
+// named or positional.
+//
+//
+// Resources:
+//
+//   - The DebugTuple helper for formatting tuple structs:
+//     https://doc.rust-lang.org/std/fmt/struct.DebugTuple.html
+
+use derive_debug::CustomDebug;
+use std::marker::PhantomData;
+
+#[derive(CustomDebug)]
+pub struct Flags(&'static str, #[debug = "0b{:08b}"] u8);
+
+#[derive(CustomDebug)]
+pub struct Unit;
+
+#[derive(CustomDebug)]
+pub enum Message<T> {
+    Quit,
+    Move {
+        x: i32,
+        #[debug = "{}px"]
+        y: i32,
+    },
+    Write(T, #[debug = "{:#x}"] u32),
+    Marker(PhantomData<T>),
+}
+
+#[derive(CustomDebug)]
+pub enum Never {}
+
+fn main() {
+    assert_eq!(format!("{:?}", Flags("F", 0b11)), r#"Flags("F", 0b00000011)"#);
+    assert_eq!(format!("{:?}", Unit), "Unit");
+
+    let messages: [Message<&str>; 4] = [
+        Message::Quit,
+        Message::Move { x: 1, y: 2 },
+        Message::Write("hi", 255),
+        Message::Marker(PhantomData),
+    ];
+    let debug: Vec<String> = messages.iter().map(|m| format!("{:?}", m)).collect();
+    assert_eq!(
+        debug,
+        [
+            "Quit",
+            "Move { x: 1, y: 2px }",
+            r#"Write("hi", 0xff)"#,
+            "Marker(PhantomData<&str>)",
+        ]
+    );
+
+    fn assert_debug<F: std::fmt::Debug>() {}
+    assert_debug::<Never>();
+}
@@ -0,0 +1,57 @@
+// Some fields must never end up in logs. #[debug(skip)] leaves a field out of
+// the output entirely, and #[debug(redact)] prints a placeholder instead of the
+// value: `<redacted>` by default, or the string given as #[debug(redact =
+// "...")].
+//
+// When any field is skipped the output ends with `..`, the same way as
+// DebugStruct::finish_non_exhaustive, so that readers know something is
+// missing. Neither a skipped nor a redacted field requires its type to
+// implement Debug.
+//
+//
+// Resources:
+//
+//   - DebugStruct::finish_non_exhaustive:
+//     https://doc.rust-lang.org/std/fmt/struct.DebugStruct.html#method.finish_non_exhaustive
+
+use derive_debug::CustomDebug;
+
+pub struct Secret;
+
+#[derive(CustomDebug)]
+pub struct Login {
+    user: &'static str,
+    #[debug(redact)]
+    password: Secret,
+    #[debug(redact = "***")]
+    token: u64,
+    #[debug(skip)]
+    cache: Secret,
+}
+
+#[derive(CustomDebug)]
+pub struct Credentials(&'static str, #[debug(redact)] &'static str);
+
+#[derive(CustomDebug)]
+pub enum Auth<T> {
+    Anonymous,
+    Key(#[debug(skip)] T),
+}
+
+fn main() {
+    let login = Login {
+        user: "root",
+        password: Secret,
+        token: 42,
+        cache: Secret,
+    };
+    assert_eq!(
+        format!("{:?}", login),
+        r#"Login { user: "root", password: <redacted>, token: ***, .. }"#,
+    );
+    assert_eq!(
+        format!("{:?}", Credentials("root", "hunter2")),
+        r#"Credentials("root", <redacted>)"#,
+    );
+    assert_eq!(format!("{:?}", Auth::Key(Secret)), "Key(..)");
+}
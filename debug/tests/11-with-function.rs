@@ -0,0 +1,62 @@
+// A format string is not always enough: byte buffers read best as hex, and
+// large collections should be cut short. #[debug(with = "path")] names a
+// function that formats the field itself:
+//
+//     fn fmt(value: &T, f: &mut fmt::Formatter) -> fmt::Result
+//
+// The function is called with a reference to the field, so the usual deref
+// coercions apply (a Vec<u8> field can be passed to a function taking &[u8]).
+// The field's type does not need to implement Debug.
+
+use derive_debug::CustomDebug;
+use std::fmt;
+use std::time::Duration;
+
+mod hex {
+    use std::fmt;
+
+    pub fn fmt(bytes: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
+        for b in bytes {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+fn seconds(d: &Duration, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}s", d.as_secs())
+}
+
+fn len<T>(v: &[T], f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "[{} items]", v.len())
+}
+
+pub struct Opaque;
+
+#[derive(CustomDebug)]
+pub struct Packet<T> {
+    #[debug(with = "hex::fmt")]
+    payload: Vec<u8>,
+    #[debug(with = "seconds")]
+    timeout: Duration,
+    #[debug(with = "len")]
+    items: Vec<T>,
+}
+
+#[derive(CustomDebug)]
+pub enum Frame {
+    Data(#[debug(with = "hex::fmt")] [u8; 2]),
+}
+
+fn main() {
+    let packet = Packet {
+        payload: vec![0xde, 0xad, 0xbe, 0xef],
+        timeout: Duration::from_secs(30),
+        items: vec![Opaque, Opaque],
+    };
+    assert_eq!(
+        format!("{:?}", packet),
+        "Packet { payload: deadbeef, timeout: 30s, items: [2 items] }",
+    );
+    assert_eq!(format!("{:?}", Frame::Data([1, 2])), "Data(0102)");
+}
@@ -0,0 +1,60 @@
+// The escape hatch from test 08 is extended in three ways.
+//
+// A #[debug(bound = "...")] attribute on a field replaces only the bounds that
+// would have been inferred from that field's type, leaving the inference for
+// every other field alone:
+//
+//     impl<T: Trait, U> Debug for Wrapper<T, U>
+//     where
+//         U: Debug,
+//         T::Value: Debug,
+//     {...}
+//
+// The bound string may hold several comma separated predicates, and the
+// container may carry several bound attributes, all of which are added.
+//
+// Attributes on the container that do not belong to this macro, like doc
+// comments or lints, are left alone.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+struct Field<T: Trait> {
+    values: Vec<T::Value>,
+}
+
+/// Documentation is not a debug attribute.
+#[allow(dead_code)]
+#[derive(CustomDebug)]
+pub struct Wrapper<T: Trait, U> {
+    #[debug(bound = "T::Value: Debug")]
+    field: Field<T>,
+    normal: U,
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "T::Value: Debug, U: Debug")]
+#[debug(bound = "V: Clone + Debug")]
+pub struct Many<T: Trait, U, V> {
+    field: Field<T>,
+    normal: U,
+    other: V,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    assert_debug::<Wrapper<Id, u8>>();
+    assert_debug::<Many<Id, u8, String>>();
+}
@@ -0,0 +1,51 @@
+// Type parameters are not only found directly in a path. The bound inference
+// looks through every shape of type that formats its contents:
+//
+//   - tuples `(T, U)`, arrays `[T::Value; 4]` and slices,
+//   - references `&'a T`,
+//   - qualified paths `<T as Trait>::Value`,
+//   - the generic arguments of any other path type.
+//
+// and collects every distinct bound it finds, so that a parameter used through
+// two different associated types gets a bound for each of them:
+//
+//     impl<T: Trait> Debug for Field<T>
+//     where
+//         T::Value: Debug,
+//         T::Other: Debug,
+//     {...}
+//
+// Function pointers and trait objects print without formatting the parameter,
+// so a field like `fn(T)` adds no bound at all.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub trait Trait {
+    type Value;
+    type Other;
+}
+
+#[derive(CustomDebug)]
+pub struct Shapes<'a, T: Trait, U, V, W> {
+    pair: (U, u8),
+    array: [T::Value; 4],
+    other: Option<T::Other>,
+    reference: &'a V,
+    qualified: <T as Trait>::Value,
+    slice: &'a [W],
+    callback: fn(T) -> T,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    impl Trait for NotDebug {
+        type Value = u8;
+        type Other = String;
+    }
+
+    assert_debug::<Shapes<NotDebug, i32, &str, bool>>();
+}
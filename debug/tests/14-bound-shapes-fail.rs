@@ -0,0 +1,37 @@
+// The bounds inferred in the previous test are real requirements: a type
+// parameter reached through any of those shapes which does not implement
+// Debug makes the impl inapplicable.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Tuple<T>((T, u8));
+
+#[derive(CustomDebug)]
+pub struct Array<T: Trait>([T::Value; 4]);
+
+#[derive(CustomDebug)]
+pub struct Reference<'a, T>(&'a T);
+
+#[derive(CustomDebug)]
+pub struct Qualified<T: Trait>(<T as Trait>::Value);
+
+fn assert_debug<F: Debug>() {}
+
+struct NotDebug;
+
+impl Trait for NotDebug {
+    type Value = NotDebug;
+}
+
+fn main() {
+    assert_debug::<Tuple<NotDebug>>();
+    assert_debug::<Array<NotDebug>>();
+    assert_debug::<Reference<NotDebug>>();
+    assert_debug::<Qualified<NotDebug>>();
+}
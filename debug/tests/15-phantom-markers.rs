@@ -0,0 +1,76 @@
+// PhantomData is not the only type whose Debug impl does not depend on its type
+// parameter. Applications often have their own marker wrappers like typed ids
+// that only carry a type for the compiler's benefit:
+//
+//     pub struct Id<T> {
+//         raw: u64,
+//         marker: PhantomData<T>,
+//     }
+//
+//     impl<T> Debug for Id<T> {...}
+//
+// The container attribute #[debug(phantom = "Tagged, Id")] lists such types;
+// a type parameter found only inside of them gets no `Debug` bound. A single
+// name matches that type under any path, a longer path has to match exactly.
+//
+// PhantomData itself is also recognized when written out in full as
+// core::marker::PhantomData or std::marker::PhantomData.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+pub struct Id<T> {
+    raw: u64,
+    marker: PhantomData<T>,
+}
+
+impl<T> Debug for Id<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.raw)
+    }
+}
+
+mod tag {
+    use std::fmt::{self, Debug};
+    use std::marker::PhantomData;
+
+    pub struct Tagged<T>(pub PhantomData<T>);
+
+    impl<T> Debug for Tagged<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("Tagged")
+        }
+    }
+}
+
+#[derive(CustomDebug)]
+#[debug(phantom = "Id, tag::Tagged")]
+pub struct User<T, U> {
+    id: Id<T>,
+    tag: tag::Tagged<U>,
+}
+
+#[derive(CustomDebug)]
+pub struct Marker<T, U> {
+    core: core::marker::PhantomData<T>,
+    std: ::std::marker::PhantomData<U>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    assert_debug::<User<NotDebug, NotDebug>>();
+    assert_debug::<Marker<NotDebug, NotDebug>>();
+
+    let user: User<NotDebug, NotDebug> = User {
+        id: Id {
+            raw: 7,
+            marker: PhantomData,
+        },
+        tag: tag::Tagged(PhantomData),
+    };
+    assert_eq!(format!("{:?}", user), "User { id: #7, tag: Tagged }");
+}
@@ -0,0 +1,93 @@
+// Alongside CustomDebug the crate provides a CustomDisplay derive, for the
+// user facing text of a type. The output is given as a template on the struct,
+// or on each variant of an enum, whose placeholders name the fields:
+//
+//     #[derive(CustomDisplay)]
+//     #[display("{name} ({id})")]
+//     pub struct User {
+//         name: String,
+//         id: u32,
+//     }
+//
+// Tuple fields are named by their index, `{0}`. Unit variants without a
+// template display as their own name.
+//
+// The bounds on type parameters are inferred the same way as for CustomDebug,
+// except that the trait comes from the placeholder: `{value}` needs Display,
+// `{value:?}` needs Debug, `{value:x}` needs LowerHex and `{value:p}` needs
+// Pointer, which prints the pointer held by the field. Fields that are not
+// mentioned by the template need nothing at all.
+
+use derive_debug::CustomDisplay;
+use std::fmt::{Debug, Display};
+use std::marker::PhantomData;
+
+#[derive(CustomDisplay)]
+#[display("{name} ({id})")]
+pub struct User {
+    name: String,
+    id: u32,
+}
+
+#[derive(CustomDisplay)]
+#[display("{0}..{1}")]
+pub struct Span(usize, usize);
+
+#[derive(CustomDisplay)]
+pub enum Shape {
+    #[display("circle of radius {radius:.1}")]
+    Circle { radius: f64 },
+    #[display("{0}x{1} {{rectangle}}")]
+    Rectangle(u32, u32),
+    Point,
+}
+
+#[derive(CustomDisplay)]
+#[display("{label}: {value:?} at {addr:#x}")]
+pub struct Entry<T, U, A, M> {
+    label: T,
+    value: U,
+    addr: A,
+    marker: PhantomData<M>,
+}
+
+#[derive(CustomDisplay)]
+#[display("at {0:p}")]
+pub struct Address<P>(P);
+
+fn assert_display<F: Display>() {}
+
+fn main() {
+    let byte = 0u8;
+    let ptr: *const u8 = &byte;
+    assert_display::<Address<*const u8>>();
+    assert_eq!(Address(ptr).to_string(), format!("at {:p}", ptr));
+
+    let user = User {
+        name: "ferris".to_owned(),
+        id: 42,
+    };
+    assert_eq!(user.to_string(), "ferris (42)");
+    assert_eq!(Span(3, 7).to_string(), "3..7");
+
+    assert_eq!(Shape::Circle { radius: 2.0 }.to_string(), "circle of radius 2.0");
+    assert_eq!(Shape::Rectangle(2, 3).to_string(), "2x3 {rectangle}");
+    assert_eq!(Shape::Point.to_string(), "Point");
+
+    #[derive(Debug)]
+    struct DebugOnly;
+    struct Neither;
+
+    fn check<U: Debug>() {
+        assert_display::<Entry<&'static str, U, u16, Neither>>();
+    }
+    check::<DebugOnly>();
+
+    let entry: Entry<_, _, _, Neither> = Entry {
+        label: "ready",
+        value: DebugOnly,
+        addr: 255u16,
+        marker: PhantomData,
+    };
+    assert_eq!(entry.to_string(), "ready: DebugOnly at 0xff");
+}
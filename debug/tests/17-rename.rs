@@ -0,0 +1,70 @@
+// Structs that mirror a wire protocol use Rust naming conventions, while the
+// logs are easier to read with the names used by the protocol itself.
+//
+// #[debug(rename = "...")] replaces the printed name of a field, of the struct
+// when given on the struct, or of a variant when given on an enum variant.
+//
+// #[debug(rename_all = "...")] on the container converts the case of every
+// field name of a struct, or of every variant name of an enum. The rules are
+// spelled as in serde: lowercase, UPPERCASE, PascalCase, camelCase,
+// snake_case, SCREAMING_SNAKE_CASE, kebab-case and SCREAMING-KEBAB-CASE. An
+// explicit rename takes priority over the rule.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(rename = "LoginRequest", rename_all = "camelCase")]
+pub struct Login {
+    user_name: &'static str,
+    #[debug(rename = "pwd")]
+    password_hash: u64,
+    r#type: u8,
+}
+
+#[derive(CustomDebug)]
+#[debug(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Status {
+    NotFound,
+    #[debug(rename = "oops")]
+    InternalError { retry_after: u32 },
+    TooManyRequests(u32),
+}
+
+#[derive(CustomDebug)]
+#[debug(rename_all = "kebab-case")]
+pub struct Headers {
+    content_length: usize,
+    #[debug(rename = "X-Id")]
+    request_id: u32,
+}
+
+fn main() {
+    let login = Login {
+        user_name: "ferris",
+        password_hash: 7,
+        r#type: 1,
+    };
+    assert_eq!(
+        format!("{:?}", login),
+        r#"LoginRequest { userName: "ferris", pwd: 7, type: 1 }"#,
+    );
+
+    assert_eq!(format!("{:?}", Status::NotFound), "NOT_FOUND");
+    assert_eq!(
+        format!("{:?}", Status::InternalError { retry_after: 3 }),
+        "oops { retry_after: 3 }",
+    );
+    assert_eq!(
+        format!("{:?}", Status::TooManyRequests(9)),
+        "TOO_MANY_REQUESTS(9)",
+    );
+
+    let headers = Headers {
+        content_length: 10,
+        request_id: 4,
+    };
+    assert_eq!(
+        format!("{:?}", headers),
+        "Headers { content-length: 10, X-Id: 4 }",
+    );
+}
@@ -0,0 +1,116 @@
+// Deeply nested configuration is hard to read once its Debug output has been
+// squeezed onto a single log line. Two attributes make the output flatter.
+//
+// #[debug(flatten)] on a field prints the fields of the field's own struct in
+// place of the field itself, as if they were declared in the parent:
+//
+//     Server { host: "localhost", port: 8080, timeout: 30 }
+//
+// rather than
+//
+//     Server { host: "localhost", net: Net { port: 8080, timeout: 30 } }
+//
+// The flattened struct has to derive CustomDebug as well and opt in with
+// #[debug(flattenable)]. That generates a public, doc(hidden) inherent method
+// `__debug_fields` adding its fields to someone else's DebugStruct, which the
+// parent calls for the flattened field. Without the opt-in no method is added
+// to the type. Fields skipped inside the flattened struct still end the
+// output with `..`.
+//
+// #[debug(transparent)] on a newtype prints only the wrapped value, with none
+// of the wrapper's name around it.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(flattenable)]
+pub struct Net {
+    port: u16,
+    #[debug(rename = "timeout_s")]
+    timeout: u32,
+}
+
+#[derive(CustomDebug)]
+#[debug(flattenable)]
+pub struct Auth {
+    user: &'static str,
+    #[debug(skip)]
+    password: &'static str,
+}
+
+#[derive(CustomDebug)]
+pub struct Server {
+    host: &'static str,
+    #[debug(flatten)]
+    net: Net,
+}
+
+#[derive(CustomDebug)]
+pub enum Upstream {
+    Open {
+        #[debug(flatten)]
+        net: Net,
+    },
+    Private {
+        #[debug(flatten)]
+        net: Net,
+        #[debug(flatten)]
+        auth: Auth,
+    },
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Port(u16);
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Name {
+    #[debug = "<{}>"]
+    inner: &'static str,
+}
+
+fn main() {
+    let server = Server {
+        host: "localhost",
+        net: Net {
+            port: 8080,
+            timeout: 30,
+        },
+    };
+    assert_eq!(
+        format!("{:?}", server),
+        r#"Server { host: "localhost", port: 8080, timeout_s: 30 }"#,
+    );
+    assert_eq!(
+        format!("{:#?}", server),
+        "Server {\n    host: \"localhost\",\n    port: 8080,\n    timeout_s: 30,\n}",
+    );
+
+    let open = Upstream::Open {
+        net: Net {
+            port: 80,
+            timeout: 5,
+        },
+    };
+    assert_eq!(format!("{:?}", open), "Open { port: 80, timeout_s: 5 }");
+
+    let private = Upstream::Private {
+        net: Net {
+            port: 443,
+            timeout: 5,
+        },
+        auth: Auth {
+            user: "ferris",
+            password: "hunter2",
+        },
+    };
+    assert_eq!(
+        format!("{:?}", private),
+        r#"Private { port: 443, timeout_s: 5, user: "ferris", .. }"#,
+    );
+
+    assert_eq!(format!("{:?}", Port(22)), "22");
+    assert_eq!(format!("{:05?}", Port(22)), "00022");
+    assert_eq!(format!("{:?}", Name { inner: "x" }), "<x>");
+}
@@ -0,0 +1,93 @@
+// Structs with many optional fields print walls of `field: None`. Two
+// attributes leave such fields out of the output, deciding at runtime inside
+// of the generated fmt.
+//
+// #[debug(skip_if = "path")] on a named field calls `path(&value)` and omits
+// the field whenever it returns true, with any function taking a reference to
+// the field's type, such as Option::is_none or str::is_empty.
+//
+// #[debug(skip_none)] on the container does the same for every named field
+// whose type is an Option or a Vec, leaving out the `None` options and the
+// empty vectors. A field with its own skip_if keeps using it.
+//
+// Unlike #[debug(skip)] the output does not end with `..`: nothing that holds
+// a value has been hidden.
+
+use derive_debug::CustomDebug;
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+#[derive(CustomDebug)]
+pub struct Request {
+    path: &'static str,
+    #[debug(skip_if = "Option::is_none")]
+    query: Option<&'static str>,
+    #[debug(skip_if = "is_zero")]
+    retries: u32,
+}
+
+#[derive(CustomDebug)]
+#[debug(skip_none)]
+pub struct Profile {
+    name: &'static str,
+    email: Option<&'static str>,
+    tags: Vec<&'static str>,
+    #[debug(skip_if = "str::is_empty")]
+    bio: &'static str,
+}
+
+#[derive(CustomDebug)]
+#[debug(skip_none)]
+pub enum Event {
+    Login { user: &'static str, ip: Option<[u8; 4]> },
+    Logout(Option<u32>),
+}
+
+fn main() {
+    let request = Request {
+        path: "/",
+        query: None,
+        retries: 0,
+    };
+    assert_eq!(format!("{:?}", request), r#"Request { path: "/" }"#);
+
+    let request = Request {
+        path: "/search",
+        query: Some("q=rust"),
+        retries: 2,
+    };
+    assert_eq!(
+        format!("{:?}", request),
+        r#"Request { path: "/search", query: Some("q=rust"), retries: 2 }"#,
+    );
+
+    let profile = Profile {
+        name: "ferris",
+        email: None,
+        tags: Vec::new(),
+        bio: "",
+    };
+    assert_eq!(format!("{:?}", profile), r#"Profile { name: "ferris" }"#);
+
+    let profile = Profile {
+        name: "ferris",
+        email: Some("ferris@example.com"),
+        tags: vec!["crab"],
+        bio: "",
+    };
+    assert_eq!(
+        format!("{:?}", profile),
+        r#"Profile { name: "ferris", email: Some("ferris@example.com"), tags: ["crab"] }"#,
+    );
+
+    let login = Event::Login {
+        user: "ferris",
+        ip: None,
+    };
+    assert_eq!(format!("{:?}", login), r#"Login { user: "ferris" }"#);
+
+    // Tuple fields are positional, and are always printed.
+    assert_eq!(format!("{:?}", Event::Logout(None)), "Logout(None)");
+}
@@ -0,0 +1,100 @@
+// A #[debug = "..."] template used to be handed to format_args! together with
+// the value of the field as its only argument. Templates can now also print
+// the other fields of the struct or variant, by name:
+//
+//     #[derive(CustomDebug)]
+//     pub struct Measure {
+//         #[debug("{}{self.unit}")]
+//         value: f64,
+//         #[debug(skip)]
+//         unit: &'static str,
+//     }
+//
+// `{}` and `{0}` are the value of the field itself and `{self.name}`, or
+// `{self.0}` in tuple structs, the value of any field. The list form
+// #[debug("...")] is accepted alongside #[debug = "..."].
+//
+// The template is checked while the derive expands, so that a mistake is
+// reported on the template rather than somewhere in the generated code. The
+// bounds follow the placeholders: a generic field printed as `{:x}` needs
+// LowerHex instead of Debug, and one printed as `{:p}` needs Pointer.
+
+use derive_debug::CustomDebug;
+use std::fmt::{Debug, LowerHex};
+
+#[derive(CustomDebug)]
+pub struct Measure {
+    #[debug("{}{self.unit}")]
+    value: f64,
+    #[debug(skip)]
+    unit: &'static str,
+}
+
+#[derive(CustomDebug)]
+pub struct Range(#[debug = "{0}..{self.1}"] u32, #[debug(skip)] u32);
+
+#[derive(CustomDebug)]
+pub enum Reading {
+    Temperature {
+        #[debug("{:.1}°{self.scale}")]
+        degrees: f32,
+        #[debug(skip)]
+        scale: char,
+    },
+}
+
+#[derive(CustomDebug)]
+pub struct Register<T> {
+    #[debug = "{:#06x} {{{self.name}}}"]
+    addr: T,
+    name: &'static str,
+}
+
+#[derive(CustomDebug)]
+pub struct Handle<P> {
+    #[debug = "{:p}"]
+    ptr: P,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let m = Measure {
+        value: 2.5,
+        unit: "kg",
+    };
+    assert_eq!(format!("{:?}", m), "Measure { value: 2.5kg, .. }");
+    assert_eq!(format!("{:?}", Range(3, 7)), "Range(3..7, ..)");
+
+    let r = Reading::Temperature {
+        degrees: 21.54,
+        scale: 'C',
+    };
+    assert_eq!(format!("{:?}", r), "Temperature { degrees: 21.5°C, .. }");
+
+    // Only LowerHex is needed from the type parameter.
+    #[derive(Clone, Copy)]
+    struct Addr(u16);
+    impl LowerHex for Addr {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            LowerHex::fmt(&self.0, f)
+        }
+    }
+    assert_debug::<Register<Addr>>();
+
+    // A raw pointer is Pointer but not Display.
+    assert_debug::<Handle<*const u8>>();
+    let byte = 0u8;
+    let ptr: *const u8 = &byte;
+    let handle = Handle { ptr };
+    assert_eq!(format!("{:?}", handle), format!("Handle {{ ptr: {:p} }}", ptr));
+
+    let reg = Register {
+        addr: Addr(0x1f),
+        name: "status",
+    };
+    assert_eq!(
+        format!("{:?}", reg),
+        r#"Register { addr: 0x001f {status}, name: "status" }"#,
+    );
+}
@@ -0,0 +1,43 @@
+// Mistakes in a #[debug = "..."] template are reported on the template itself.
+//
+// On a nightly compiler the error points at the placeholder inside the
+// template. Stable compilers cannot point into a string literal from a
+// procedural macro, so there the whole template is underlined, as in the
+// expected output below.
+//
+// Width and precision taken from arguments, `{:1$}` or `{:.*}`, are rejected
+// because the generated code has no such arguments to pass.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct UnknownField {
+    #[debug = "{}{self.unit}"]
+    value: f64,
+}
+
+#[derive(CustomDebug)]
+pub struct UnknownArgument {
+    #[debug = "{value}"]
+    value: f64,
+}
+
+#[derive(CustomDebug)]
+pub struct Unmatched {
+    #[debug = "{:?"]
+    value: f64,
+}
+
+#[derive(CustomDebug)]
+pub struct WidthArgument {
+    #[debug = "{:1$}"]
+    value: f64,
+}
+
+#[derive(CustomDebug)]
+pub struct PrecisionArgument {
+    #[debug = "{:.*}"]
+    value: f64,
+}
+
+fn main() {}
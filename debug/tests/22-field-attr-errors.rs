@@ -0,0 +1,35 @@
+// Every malformed #[debug] attribute on a field is a compile error pointing at
+// the mistake, rather than being ignored or crashing the derive. All of the
+// mistakes of a type are reported together.
+
+use derive_debug::CustomDebug;
+
+fn show(_: &u8, _: &mut std::fmt::Formatter) -> std::fmt::Result {
+    Ok(())
+}
+
+#[derive(CustomDebug)]
+pub struct Mistakes {
+    #[debug]
+    bare: u8,
+    #[debug = 5]
+    not_a_string: u8,
+    #[debug(typo)]
+    unknown: u8,
+    #[debug(with = show)]
+    unquoted: u8,
+    #[debug(skip, redact)]
+    conflicting: u8,
+    #[debug = "{}"]
+    #[debug("{:?}")]
+    two_templates: u8,
+    #[debug(bound = "T Debug")]
+    bad_bound: u8,
+    #[debug(flatten, rename = "x")]
+    renamed_flatten: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Tuple(#[debug(rename = "x")] u8, #[debug(skip_if = "Option::is_none")] Option<u8>);
+
+fn main() {}
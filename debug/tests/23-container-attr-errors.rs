@@ -0,0 +1,41 @@
+// Malformed #[debug] attributes on the struct, the enum or a variant are
+// compile errors as well.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug = "Struct"]
+pub struct Template {
+    x: u8,
+}
+
+#[derive(CustomDebug)]
+#[debug(rename_all = "Camel", unknown)]
+pub struct RenameRule {
+    x: u8,
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Transparent {
+    x: u8,
+    y: u8,
+}
+
+#[derive(CustomDebug)]
+#[debug(flattenable)]
+pub struct Flattenable(u8);
+
+#[derive(CustomDebug)]
+#[debug(rename = "Other")]
+pub enum RenamedEnum {
+    A,
+}
+
+#[derive(CustomDebug)]
+pub enum Variant {
+    #[debug(bound = "u8: Copy")]
+    A(u8),
+}
+
+fn main() {}
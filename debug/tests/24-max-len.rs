@@ -0,0 +1,66 @@
+// Logging a struct that holds a multi-megabyte buffer floods the logs with
+// its whole content. #[debug(max_len = N)] on a field prints only the first N
+// elements of a collection, or the first N characters of a string, followed
+// by how many were left out:
+//
+//     Packet { payload: [1, 2, 3, … (997 more)], note: "hel"… (2 more) }
+//
+// The container attribute #[debug(max_len = N)] is the default for every field
+// whose type is a String, a str, a Vec, a VecDeque, an array or a slice.
+//
+// The derive generates a small wrapper type inside of fmt that does the
+// truncation, so the output keeps working with {:#?}.
+
+use derive_debug::CustomDebug;
+use std::collections::VecDeque;
+
+#[derive(CustomDebug)]
+pub struct Packet {
+    #[debug(max_len = 3)]
+    payload: Vec<u8>,
+    #[debug(max_len = 3)]
+    note: String,
+    #[debug(max_len = 10)]
+    short: &'static str,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_len = 2)]
+pub struct Buffers<'a> {
+    queue: VecDeque<u32>,
+    window: [u8; 4],
+    slice: &'a [i32],
+    name: &'a str,
+    #[debug(max_len = 4)]
+    wide: Vec<char>,
+    count: usize,
+}
+
+fn main() {
+    let packet = Packet {
+        payload: (1..=100).collect(),
+        note: "héllo".to_owned(),
+        short: "ok",
+    };
+    assert_eq!(
+        format!("{:?}", packet),
+        r#"Packet { payload: [1, 2, 3, … (97 more)], note: "hél"… (2 more), short: "ok" }"#,
+    );
+    assert_eq!(
+        format!("{:#?}", packet),
+        "Packet {\n    payload: [\n        1,\n        2,\n        3,\n        … (97 more),\n    ],\n    note: \"hél\"… (2 more),\n    short: \"ok\",\n}",
+    );
+
+    let buffers = Buffers {
+        queue: (0..5).collect(),
+        window: [9; 4],
+        slice: &[-1, -2],
+        name: "buffers",
+        wide: vec!['a'; 5],
+        count: 12345,
+    };
+    assert_eq!(
+        format!("{:?}", buffers),
+        r#"Buffers { queue: [0, 1, … (3 more)], window: [9, 9, … (2 more)], slice: [-1, -2], name: "bu"… (5 more), wide: ['a', 'a', 'a', 'a', … (1 more)], count: 12345 }"#,
+    );
+}
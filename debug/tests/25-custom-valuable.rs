@@ -0,0 +1,107 @@
+// Structured logging wants key/value pairs rather than one Debug string. The
+// CustomValuable derive generates a method that hands every field to a
+// visitor, as the field name and the field as a `&dyn Debug`:
+//
+//     impl Request {
+//         pub fn visit_fields(&self, visitor: &mut impl FieldVisitor) {...}
+//     }
+//
+// The visitor trait belongs to the application, usually as an adapter to its
+// logging library. It is the `FieldVisitor` in scope, or the trait given by
+// #[valuable(visitor = "path::to::Trait")], and needs one method:
+//
+//     fn visit_field(&mut self, name: &str, value: &dyn Debug);
+//
+// The values follow the same #[debug(...)] attributes as CustomDebug: skipped
+// fields are not visited, redacted ones are visited as their placeholder, and
+// templates, `with`, `rename`, `skip_if` and `max_len` all apply. A flattened
+// field visits the fields of its own struct, which has to derive
+// CustomValuable too.
+
+use derive_debug::CustomValuable;
+use std::fmt::Debug;
+
+pub trait FieldVisitor {
+    fn visit_field(&mut self, name: &str, value: &dyn Debug);
+}
+
+#[derive(Default)]
+pub struct Collect(Vec<(String, String)>);
+
+impl FieldVisitor for Collect {
+    fn visit_field(&mut self, name: &str, value: &dyn Debug) {
+        self.0.push((name.to_owned(), format!("{:?}", value)));
+    }
+}
+
+#[derive(CustomValuable)]
+pub struct Peer {
+    addr: &'static str,
+    port: u16,
+}
+
+#[derive(CustomValuable)]
+#[debug(rename_all = "camelCase")]
+pub struct Request<T> {
+    request_id: u64,
+    #[debug(redact)]
+    api_key: String,
+    #[debug(skip)]
+    cache: Vec<u8>,
+    #[debug = "{}ms"]
+    elapsed: u32,
+    #[debug(skip_if = "Option::is_none")]
+    user: Option<T>,
+    #[debug(flatten)]
+    peer: Peer,
+}
+
+mod log {
+    pub trait KeyValues {
+        fn visit_field(&mut self, name: &str, value: &dyn std::fmt::Debug);
+    }
+}
+
+#[derive(CustomValuable)]
+#[valuable(visitor = "log::KeyValues")]
+pub struct Pair(u8, #[debug(max_len = 2)] Vec<u8>);
+
+struct Line(String);
+
+impl log::KeyValues for Line {
+    fn visit_field(&mut self, name: &str, value: &dyn Debug) {
+        self.0 += &format!("{}={:?} ", name, value);
+    }
+}
+
+fn main() {
+    let request = Request {
+        request_id: 7,
+        api_key: "secret".to_owned(),
+        cache: vec![1, 2, 3],
+        elapsed: 12,
+        user: None::<&str>,
+        peer: Peer {
+            addr: "10.0.0.1",
+            port: 443,
+        },
+    };
+    let mut collect = Collect::default();
+    request.visit_fields(&mut collect);
+    let expected = [
+        ("requestId", "7"),
+        ("apiKey", "<redacted>"),
+        ("elapsed", "12ms"),
+        ("addr", "\"10.0.0.1\""),
+        ("port", "443"),
+    ];
+    let expected: Vec<(String, String)> = expected
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    assert_eq!(collect.0, expected);
+
+    let mut line = Line(String::new());
+    Pair(1, vec![1, 2, 3]).visit_fields(&mut line);
+    assert_eq!(line.0, "0=1 1=[1, 2, … (1 more)] ");
+}
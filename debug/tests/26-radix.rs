@@ -0,0 +1,102 @@
+// Binary protocols are easier to debug with their integers printed in hex or
+// binary. Instead of a hand-written template like #[debug = "0b{:08b}"], a
+// field can say #[debug(hex)], #[debug(bin)] or #[debug(octal)]:
+//
+//     Header { flags: 0b101, kind: 0x1f }
+//
+// With the alternate {:#?} flag the digits are zero padded to the full width
+// of the integer's type and grouped with underscores, four hex digits, eight
+// binary digits or three octal digits at a time:
+//
+//     Header {
+//         flags: 0b00000101,
+//         kind: 0x001f,
+//     }
+//
+// On the container, the attribute applies to every field that is a primitive
+// integer. A generic field printed in hex needs LowerHex rather than Debug. A
+// field that borrows its integer is padded to the width of the integer, not of
+// the reference.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug, LowerHex};
+
+#[derive(CustomDebug)]
+pub struct Header {
+    #[debug(bin)]
+    flags: u8,
+    #[debug(hex)]
+    kind: u16,
+    #[debug(octal)]
+    mode: u32,
+    len: usize,
+}
+
+#[derive(CustomDebug)]
+#[debug(hex)]
+pub struct Registers {
+    pc: u64,
+    status: i8,
+    name: &'static str,
+    #[debug(bin)]
+    mask: u16,
+}
+
+#[derive(CustomDebug)]
+pub struct Borrowed<'a> {
+    #[debug(hex)]
+    byte: &'a u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Word<T> {
+    #[debug(hex)]
+    value: T,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let header = Header {
+        flags: 5,
+        kind: 0x1f,
+        mode: 0o644,
+        len: 3,
+    };
+    assert_eq!(
+        format!("{:?}", header),
+        "Header { flags: 0b101, kind: 0x1f, mode: 0o644, len: 3 }",
+    );
+    assert_eq!(
+        format!("{:#?}", header),
+        "Header {\n    flags: 0b00000101,\n    kind: 0x001f,\n    mode: 0o00_000_000_644,\n    len: 3,\n}",
+    );
+
+    let registers = Registers {
+        pc: 0xdead_beef,
+        status: -1,
+        name: "cpu0",
+        mask: 0x0f0f,
+    };
+    assert_eq!(
+        format!("{:?}", registers),
+        r#"Registers { pc: 0xdeadbeef, status: 0xff, name: "cpu0", mask: 0b111100001111 }"#,
+    );
+    assert_eq!(
+        format!("{:#?}", registers),
+        "Registers {\n    pc: 0x0000_0000_dead_beef,\n    status: 0xff,\n    name: \"cpu0\",\n    mask: 0b00001111_00001111,\n}",
+    );
+
+    let borrowed = Borrowed { byte: &0x1f };
+    assert_eq!(format!("{:?}", borrowed), "Borrowed { byte: 0x1f }");
+    assert_eq!(format!("{:#?}", borrowed), "Borrowed {\n    byte: 0x1f,\n}");
+
+    struct HexOnly(u8);
+    impl LowerHex for HexOnly {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            LowerHex::fmt(&self.0, f)
+        }
+    }
+    assert_debug::<Word<HexOnly>>();
+    assert_eq!(format!("{:?}", Word { value: HexOnly(171) }), "Word { value: 0xab }");
+}
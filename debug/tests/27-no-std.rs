@@ -0,0 +1,74 @@
+// Firmware built with #![no_std] has no `std` crate to refer to, so the
+// generated code must not mention it. Every path the derives emit goes
+// through ::core instead, including the bounds added to the where-clause:
+//
+//     impl<T> ::core::fmt::Debug for Field<T>
+//     where
+//         T: ::core::fmt::Debug,
+//     {...}
+//
+// This test stands in for a no_std crate by declaring its own module named
+// `std`, which shadows the real one for any path written as `std::...`. None
+// of the helpers generated for templates, `with`, `max_len`, `skip_none` or
+// the radix attributes may depend on `std` or `alloc` either.
+
+#![allow(dead_code)]
+
+mod std {}
+
+use derive_debug::{CustomDebug, CustomDisplay, CustomValuable};
+
+fn show(x: &u8, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    core::write!(f, "<{}>", x)
+}
+
+pub trait FieldVisitor {
+    fn visit_field(&mut self, name: &str, value: &dyn core::fmt::Debug);
+}
+
+#[derive(CustomDebug, CustomValuable)]
+#[debug(skip_none)]
+pub struct Field<T> {
+    value: T,
+    #[debug = "{:?}{self.unit}"]
+    scaled: u32,
+    #[debug(skip)]
+    unit: &'static str,
+    #[debug(with = "show")]
+    raw: u8,
+    #[debug(max_len = 2)]
+    samples: [u16; 4],
+    #[debug(max_len = 3)]
+    label: &'static str,
+    #[debug(hex)]
+    addr: u32,
+    extra: Option<u8>,
+}
+
+#[derive(CustomDebug, CustomDisplay)]
+pub enum State<T> {
+    #[display("idle")]
+    Idle,
+    #[display("running {0}")]
+    Running(T),
+}
+
+fn main() {
+    let field = Field {
+        value: 1u8,
+        scaled: 3,
+        unit: "mV",
+        raw: 7,
+        samples: [1, 2, 3, 4],
+        label: "sensor",
+        addr: 0x40,
+        extra: None,
+    };
+    assert_eq!(
+        format!("{:?}", field),
+        r#"Field { value: 1, scaled: 3mV, raw: <7>, samples: [1, 2, … (2 more)], label: "sen"… (3 more), addr: 0x40, .. }"#,
+    );
+    assert_eq!(format!("{:?}", State::Running(2)), "Running(2)");
+    assert_eq!(format!("{}", State::Running(2)), "running 2");
+    assert_eq!(format!("{}", State::<u8>::Idle), "idle");
+}
@@ -0,0 +1,71 @@
+// The fields of a union cannot be printed one by one: nothing says which of
+// them holds a value. A union still gets a Debug impl with one of two
+// container attributes.
+//
+// #[debug(opaque)] prints only the name of the union:
+//
+//     Word { .. }
+//
+// Nothing is read from the union. Creating a union is safe and may leave some
+// of its bytes uninitialized, so a safe Debug impl cannot print its raw bytes.
+//
+// A union whose bytes are always all initialized, like a union of integers of
+// the same size, can have them printed after its name with
+// #[debug(opaque, unsafe_bytes)]. The attribute is the author's promise that
+// reading every byte is sound:
+//
+//     Raw([0x01, 0x00, 0x00, 0x00])
+//
+// #[debug(with = "path")] prints the union with a function
+// `fn(&Self, &mut fmt::Formatter) -> fmt::Result` that knows how to tell the
+// fields apart.
+//
+// A union with neither attribute is a compile error that asks for one of
+// them.
+
+use derive_debug::CustomDebug;
+use std::fmt;
+
+#[derive(CustomDebug)]
+#[debug(opaque)]
+pub union Word {
+    int: u32,
+    bytes: [u8; 4],
+}
+
+#[derive(CustomDebug)]
+#[debug(opaque, unsafe_bytes)]
+pub union Raw {
+    int: u32,
+    bytes: [u8; 4],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Tagged {
+    tag: u8,
+}
+
+#[derive(CustomDebug)]
+#[debug(with = "fmt_event")]
+pub union Event {
+    tagged: Tagged,
+    raw: u8,
+}
+
+fn fmt_event(event: &Event, f: &mut fmt::Formatter) -> fmt::Result {
+    // The tag is always written, whatever the variant.
+    write!(f, "Event#{}", unsafe { event.tagged.tag })
+}
+
+fn main() {
+    let word = Word { int: 1 };
+    assert_eq!(format!("{:?}", word), "Word { .. }");
+    assert_eq!(format!("{:#?}", word), "Word { .. }");
+
+    let raw = Raw { bytes: [1, 0, 0, 0xff] };
+    assert_eq!(format!("{:?}", raw), "Raw([0x01, 0x00, 0x00, 0xff])");
+
+    let event = Event { raw: 3 };
+    assert_eq!(format!("{:?}", event), "Event#3");
+}
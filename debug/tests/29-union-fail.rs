@@ -0,0 +1,20 @@
+// A union without #[debug(opaque)] or #[debug(with = "...")] has no way to be
+// printed. #[debug(unsafe_bytes)] only adds the bytes to #[debug(opaque)] and
+// is an error on its own.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub union Word {
+    int: u32,
+    bytes: [u8; 4],
+}
+
+#[derive(CustomDebug)]
+#[debug(unsafe_bytes)]
+pub union Raw {
+    int: u32,
+    bytes: [u8; 4],
+}
+
+fn main() {}
@@ -0,0 +1,96 @@
+// The fields left out of Debug, like caches and timestamps, are usually also
+// the ones that should not take part in equality. CustomPartialEq and
+// CustomHash are the companions of CustomDebug for that: they compare and
+// hash every field except those marked #[eq(skip)].
+//
+// Both derives read the same #[eq(...)] attributes, so a type deriving both
+// keeps the rule that equal values hash the same. Bounds on type parameters
+// are inferred the same way as for CustomDebug, with PartialEq or Hash in
+// place of Debug and only from the fields that take part, and can be given
+// by hand with #[eq(bound = "...")].
+
+use derive_debug::{CustomDebug, CustomHash, CustomPartialEq};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+pub struct Opaque;
+
+#[derive(CustomDebug, CustomPartialEq, CustomHash)]
+pub struct Document<T> {
+    id: u64,
+    body: String,
+    #[debug(skip)]
+    #[eq(skip)]
+    rendered: Option<Opaque>,
+    #[eq(skip)]
+    fetched_at: u64,
+    kind: PhantomData<T>,
+}
+
+#[derive(CustomPartialEq, CustomHash)]
+pub enum Shape {
+    Circle {
+        radius: u32,
+        #[eq(skip)]
+        label: &'static str,
+    },
+    Square(u32, #[eq(skip)] Opaque),
+    Empty,
+}
+
+#[derive(CustomPartialEq)]
+#[eq(bound = "T: PartialEq")]
+pub struct Wrapper<T>(Box<T>);
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn main() {
+    let a: Document<Opaque> = Document {
+        id: 1,
+        body: "hello".to_owned(),
+        rendered: None,
+        fetched_at: 10,
+        kind: PhantomData,
+    };
+    let b = Document {
+        rendered: Some(Opaque),
+        fetched_at: 20,
+        ..Document {
+            id: 1,
+            body: "hello".to_owned(),
+            rendered: None,
+            fetched_at: 0,
+            kind: PhantomData,
+        }
+    };
+    assert!(a == b);
+    assert_eq!(hash_of(&a), hash_of(&b));
+
+    let c = Document {
+        id: 2,
+        ..a
+    };
+    assert!(c != b);
+
+    let circle = Shape::Circle {
+        radius: 2,
+        label: "a",
+    };
+    let other = Shape::Circle {
+        radius: 2,
+        label: "b",
+    };
+    assert!(circle == other);
+    assert_eq!(hash_of(&circle), hash_of(&other));
+    assert!(Shape::Square(2, Opaque) == Shape::Square(2, Opaque));
+    assert!(Shape::Square(2, Opaque) != circle);
+    assert!(Shape::Empty == Shape::Empty);
+    assert_ne!(hash_of(&Shape::Empty), hash_of(&Shape::Square(0, Opaque)));
+
+    assert!(Wrapper(Box::new(1)) == Wrapper(Box::new(1)));
+}
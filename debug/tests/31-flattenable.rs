@@ -0,0 +1,23 @@
+// Only the structs marked #[debug(flattenable)] get the `__debug_fields`
+// method that #[debug(flatten)] relies on. Deriving CustomDebug on any other
+// struct adds nothing but the Debug impl, so the type stays free to define an
+// inherent method of that name itself.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Plain {
+    x: u8,
+}
+
+impl Plain {
+    pub fn __debug_fields(&self) -> u8 {
+        self.x
+    }
+}
+
+fn main() {
+    let plain = Plain { x: 1 };
+    assert_eq!(plain.__debug_fields(), 1);
+    assert_eq!(format!("{:?}", plain), "Plain { x: 1 }");
+}
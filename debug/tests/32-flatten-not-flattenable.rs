@@ -0,0 +1,18 @@
+// Flattening a struct that did not opt in with #[debug(flattenable)] is an
+// error pointing at the type of the flattened field.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Net {
+    port: u16,
+}
+
+#[derive(CustomDebug)]
+pub struct Server {
+    host: &'static str,
+    #[debug(flatten)]
+    net: Net,
+}
+
+fn main() {}
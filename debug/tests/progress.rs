@@ -9,4 +9,28 @@ fn tests() {
     t.pass("tests/06-bound-trouble.rs");
     t.pass("tests/07-associated-type.rs");
     t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-enum-and-tuple.rs");
+    t.pass("tests/10-skip-and-redact.rs");
+    t.pass("tests/11-with-function.rs");
+    t.pass("tests/12-field-bound.rs");
+    t.pass("tests/13-bound-shapes.rs");
+    t.compile_fail("tests/14-bound-shapes-fail.rs");
+    t.pass("tests/15-phantom-markers.rs");
+    t.pass("tests/16-custom-display.rs");
+    t.pass("tests/17-rename.rs");
+    t.pass("tests/18-flatten-and-transparent.rs");
+    t.pass("tests/19-skip-none.rs");
+    t.pass("tests/20-field-templates.rs");
+    t.compile_fail("tests/21-field-templates-fail.rs");
+    t.compile_fail("tests/22-field-attr-errors.rs");
+    t.compile_fail("tests/23-container-attr-errors.rs");
+    t.pass("tests/24-max-len.rs");
+    t.pass("tests/25-custom-valuable.rs");
+    t.pass("tests/26-radix.rs");
+    t.pass("tests/27-no-std.rs");
+    t.pass("tests/28-union.rs");
+    t.compile_fail("tests/29-union-fail.rs");
+    t.pass("tests/30-partial-eq-and-hash.rs");
+    t.pass("tests/31-flattenable.rs");
+    t.compile_fail("tests/32-flatten-not-flattenable.rs");
 }
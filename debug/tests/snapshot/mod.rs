@@ -0,0 +1,52 @@
+// Comparison of a rendered string against a file stored next to the tests,
+// shared by the rendering snapshots in tests/snapshots.rs and the expansion
+// snapshots in src/tests.rs.
+//
+// Like the .stderr files of trybuild, the stored snapshots are the expected
+// output and are checked in. Running the tests with SNAPSHOTS=overwrite
+// writes the actual output over them instead of comparing, to be reviewed in
+// the diff before committing.
+
+use std::fs;
+use std::path::Path;
+
+pub fn assert_snapshot(relative_path: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(relative_path);
+    if std::env::var_os("SNAPSHOTS").is_some_and(|v| v == "overwrite") {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = match fs::read_to_string(&path) {
+        Ok(expected) => expected,
+        Err(_) => panic!(
+            "missing snapshot {}, run the tests with SNAPSHOTS=overwrite to create it\n\n{}",
+            relative_path, actual,
+        ),
+    };
+    if expected != actual {
+        let mut diff = String::new();
+        let expected_lines: Vec<&str> = expected.lines().collect();
+        let actual_lines: Vec<&str> = actual.lines().collect();
+        for i in 0..expected_lines.len().max(actual_lines.len()) {
+            match (expected_lines.get(i), actual_lines.get(i)) {
+                (Some(e), Some(a)) if e == a => diff.push_str(&format!("  {}\n", e)),
+                (e, a) => {
+                    if let Some(e) = e {
+                        diff.push_str(&format!("- {}\n", e));
+                    }
+                    if let Some(a) = a {
+                        diff.push_str(&format!("+ {}\n", a));
+                    }
+                }
+            }
+        }
+        panic!(
+            "snapshot {} does not match, run the tests with SNAPSHOTS=overwrite to accept \
+             the new output\n\n{}",
+            relative_path, diff,
+        );
+    }
+}
@@ -0,0 +1,222 @@
+// Snapshots of the exact output of CustomDebug, both {:?} and {:#?}, for a
+// corpus of types covering every attribute. The trybuild tests check that the
+// derive compiles and print the documented output; these lock the rendering
+// of each case down so that a refactor of the generated code cannot change it
+// unnoticed.
+//
+// The expected output of each case is stored in tests/snapshots/render. After
+// an intended change to the output, run
+//
+//     SNAPSHOTS=overwrite cargo test
+//
+// and review the diff of the snapshot files. The snapshots of the generated
+// impls themselves are unit tests of the crate, in src/tests.rs.
+
+// Skipped and redacted fields are never read.
+#![allow(dead_code)]
+
+mod snapshot;
+
+use derive_debug::CustomDebug;
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+fn render<T: Debug>(name: &str, value: &T) {
+    let actual = format!("{{:?}}\n{:?}\n\n{{:#?}}\n{:#?}\n", value, value);
+    snapshot::assert_snapshot(&format!("tests/snapshots/render/{}.txt", name), &actual);
+}
+
+#[derive(CustomDebug)]
+pub struct Unit;
+
+#[derive(CustomDebug)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(CustomDebug)]
+pub struct Pair(u8, &'static str);
+
+#[derive(CustomDebug)]
+pub enum Shape {
+    Empty,
+    Circle { radius: f32 },
+    Rect(u32, u32),
+}
+
+#[derive(CustomDebug)]
+pub struct Generic<T, M> {
+    value: T,
+    list: Vec<T>,
+    marker: PhantomData<M>,
+}
+
+fn hidden(_: &u32, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("<hidden>")
+}
+
+#[derive(CustomDebug)]
+pub struct Attributes {
+    #[debug = "0b{:08b}"]
+    bitmask: u8,
+    #[debug(skip)]
+    cache: u64,
+    #[debug(redact)]
+    password: &'static str,
+    #[debug(with = "hidden")]
+    token: u32,
+    #[debug = "{}/{self.max}"]
+    current: u32,
+    max: u32,
+}
+
+#[derive(CustomDebug)]
+#[debug(rename = "Request", rename_all = "camelCase")]
+pub struct Renamed {
+    user_name: &'static str,
+    #[debug(rename = "id")]
+    request_id: u32,
+}
+
+#[derive(CustomDebug)]
+#[debug(rename_all = "kebab-case")]
+pub enum Status {
+    NotFound,
+    #[debug(rename = "oops")]
+    InternalError {
+        retry_after: u32,
+    },
+}
+
+#[derive(CustomDebug)]
+#[debug(flattenable)]
+pub struct Inner {
+    a: u8,
+    #[debug(skip)]
+    b: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Outer {
+    name: &'static str,
+    #[debug(flatten)]
+    inner: Inner,
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Id(u64);
+
+#[derive(CustomDebug)]
+#[debug(skip_none)]
+pub struct Sparse {
+    present: Option<u8>,
+    absent: Option<u8>,
+    empty: Vec<u8>,
+    #[debug(skip_if = "str::is_empty")]
+    label: &'static str,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_len = 3)]
+pub struct Truncated {
+    bytes: Vec<u8>,
+    text: String,
+    map: BTreeMap<u8, u8>,
+}
+
+#[derive(CustomDebug)]
+pub struct Radix {
+    #[debug(hex)]
+    hex: u16,
+    #[debug(bin)]
+    bin: u8,
+    #[debug(octal)]
+    octal: u32,
+    #[debug(hex)]
+    negative: i8,
+    #[debug(hex)]
+    borrowed: &'static u8,
+}
+
+#[test]
+fn snapshots() {
+    render("unit", &Unit);
+    render("point", &Point { x: 1, y: -2 });
+    render("pair", &Pair(7, "seven"));
+    render(
+        "enum",
+        &[
+            Shape::Empty,
+            Shape::Circle { radius: 1.5 },
+            Shape::Rect(2, 3),
+        ],
+    );
+    render(
+        "generic",
+        &Generic::<u8, u8> {
+            value: 1,
+            list: vec![2, 3],
+            marker: PhantomData,
+        },
+    );
+    render(
+        "attributes",
+        &Attributes {
+            bitmask: 5,
+            cache: 0,
+            password: "hunter2",
+            token: 42,
+            current: 3,
+            max: 10,
+        },
+    );
+    render(
+        "rename",
+        &Renamed {
+            user_name: "ferris",
+            request_id: 9,
+        },
+    );
+    render(
+        "rename-enum",
+        &[Status::NotFound, Status::InternalError { retry_after: 30 }],
+    );
+    render(
+        "flatten",
+        &Outer {
+            name: "outer",
+            inner: Inner { a: 1, b: 2 },
+        },
+    );
+    render("transparent", &Id(1234));
+    render(
+        "skip-none",
+        &Sparse {
+            present: Some(1),
+            absent: None,
+            empty: Vec::new(),
+            label: "",
+        },
+    );
+    render(
+        "max-len",
+        &Truncated {
+            bytes: vec![1, 2, 3, 4, 5],
+            text: "truncated".to_owned(),
+            map: (0..5).map(|i| (i, i * i)).collect(),
+        },
+    );
+    render(
+        "radix",
+        &Radix {
+            hex: 0x1f,
+            bin: 5,
+            octal: 0o755,
+            negative: -1,
+            borrowed: &0x1f,
+        },
+    );
+}
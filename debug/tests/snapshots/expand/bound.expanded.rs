@@ -0,0 +1,21 @@
+#[debug(bound = "T::Value: Debug")]
+pub struct Wrapper<T: Trait> {
+    values: Vec<T::Value>,
+}
+impl<T: Trait> ::core::fmt::Debug for Wrapper<T>
+where
+    T::Value: Debug,
+{
+    fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        {
+            let __debug = &mut fmt.debug_struct("Wrapper");
+            let __non_exhaustive = false;
+            __debug.field("values", &self.values);
+            if __non_exhaustive {
+                __debug.finish_non_exhaustive()
+            } else {
+                __debug.finish()
+            }
+        }
+    }
+}
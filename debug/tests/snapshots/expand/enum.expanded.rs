@@ -0,0 +1,30 @@
+#[debug(rename_all = "snake_case")]
+pub enum Shape {
+    Empty,
+    Circle { radius: f32 },
+    Rect(u32, #[debug(redact)] u32),
+}
+impl ::core::fmt::Debug for Shape {
+    fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        match self {
+            Self::Empty => fmt.write_str("empty"),
+            Self::Circle { radius: __self_radius } => {
+                let __debug = &mut fmt.debug_struct("circle");
+                let __non_exhaustive = false;
+                __debug.field("radius", __self_radius);
+                if __non_exhaustive {
+                    __debug.finish_non_exhaustive()
+                } else {
+                    __debug.finish()
+                }
+            }
+            Self::Rect(__self_0, __self_1) => {
+                fmt
+                    .debug_tuple("rect")
+                    .field(__self_0)
+                    .field(&::core::format_args!("{}", "<redacted>"))
+                    .finish()
+            }
+        }
+    }
+}
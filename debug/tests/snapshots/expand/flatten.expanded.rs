@@ -0,0 +1,23 @@
+pub struct Outer {
+    #[debug(flatten)]
+    inner: Inner,
+    #[debug(skip_if = "Option::is_none")]
+    extra: Option<u8>,
+}
+impl ::core::fmt::Debug for Outer {
+    fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        {
+            let __debug = &mut fmt.debug_struct("Outer");
+            let mut __non_exhaustive = false;
+            __non_exhaustive |= (&self.inner).__debug_fields(__debug);
+            if !Option::is_none(&self.extra) {
+                __debug.field("extra", &self.extra);
+            }
+            if __non_exhaustive {
+                __debug.finish_non_exhaustive()
+            } else {
+                __debug.finish()
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+#[debug(flattenable)]
+pub struct Inner {
+    a: u8,
+    #[debug(skip)]
+    b: u8,
+}
+impl ::core::fmt::Debug for Inner {
+    fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        let __debug = &mut fmt.debug_struct("Inner");
+        let __non_exhaustive = self.__debug_fields(__debug);
+        if __non_exhaustive { __debug.finish_non_exhaustive() } else { __debug.finish() }
+    }
+}
+impl Inner {
+    #[doc(hidden)]
+    pub fn __debug_fields(
+        &self,
+        __debug: &mut ::core::fmt::DebugStruct<'_, '_>,
+    ) -> bool {
+        let __non_exhaustive = true;
+        __debug.field("a", &self.a);
+        __non_exhaustive
+    }
+}
@@ -0,0 +1,25 @@
+pub struct Generic<T: Trait, M> {
+    value: T,
+    assoc: Vec<T::Value>,
+    marker: PhantomData<M>,
+}
+impl<T: Trait, M> ::core::fmt::Debug for Generic<T, M>
+where
+    T: ::core::fmt::Debug,
+    T::Value: ::core::fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        {
+            let __debug = &mut fmt.debug_struct("Generic");
+            let __non_exhaustive = false;
+            __debug.field("value", &self.value);
+            __debug.field("assoc", &self.assoc);
+            __debug.field("marker", &self.marker);
+            if __non_exhaustive {
+                __debug.finish_non_exhaustive()
+            } else {
+                __debug.finish()
+            }
+        }
+    }
+}
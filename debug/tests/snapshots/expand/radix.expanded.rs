@@ -0,0 +1,103 @@
+#[debug(hex)]
+pub struct Header {
+    kind: u16,
+    flags: u8,
+}
+impl ::core::fmt::Debug for Header {
+    fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        struct DebugRadixBuf {
+            bytes: [u8; 128],
+            len: usize,
+        }
+        impl ::core::fmt::Write for DebugRadixBuf {
+            fn write_str(&mut self, s: &str) -> ::core::fmt::Result {
+                let end = self.len + s.len();
+                self.bytes
+                    .get_mut(self.len..end)
+                    .ok_or(::core::fmt::Error)?
+                    .copy_from_slice(s.as_bytes());
+                self.len = end;
+                ::core::result::Result::Ok(())
+            }
+        }
+        struct DebugRadix<F> {
+            digits: F,
+            bits: usize,
+            prefix: &'static str,
+            digit_bits: usize,
+            group: usize,
+        }
+        impl<F> ::core::fmt::Debug for DebugRadix<F>
+        where
+            F: ::core::ops::Fn(&mut DebugRadixBuf) -> ::core::fmt::Result,
+        {
+            fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                let mut buf = DebugRadixBuf {
+                    bytes: [0; 128],
+                    len: 0,
+                };
+                (self.digits)(&mut buf)?;
+                let digits = &buf.bytes[..buf.len];
+                fmt.write_str(self.prefix)?;
+                if !fmt.alternate() {
+                    for &d in digits {
+                        ::core::fmt::Write::write_char(fmt, d as char)?;
+                    }
+                    return ::core::result::Result::Ok(());
+                }
+                let width = self.bits.div_ceil(self.digit_bits);
+                let len = ::core::cmp::max(width, digits.len());
+                let padding = len - digits.len();
+                for i in 0..len {
+                    if i > 0 && (len - i) % self.group == 0 {
+                        ::core::fmt::Write::write_char(fmt, '_')?;
+                    }
+                    let d = if i < padding { b'0' } else { digits[i - padding] };
+                    ::core::fmt::Write::write_char(fmt, d as char)?;
+                }
+                ::core::result::Result::Ok(())
+            }
+        }
+        {
+            let __debug = &mut fmt.debug_struct("Header");
+            let __non_exhaustive = false;
+            __debug
+                .field(
+                    "kind",
+                    &DebugRadix {
+                        digits: |buf: &mut DebugRadixBuf| {
+                            ::core::fmt::Write::write_fmt(
+                                buf,
+                                ::core::format_args!("{:x}", & self.kind),
+                            )
+                        },
+                        bits: ::core::mem::size_of_val(&self.kind) * 8,
+                        prefix: "0x",
+                        digit_bits: 4usize,
+                        group: 4usize,
+                    },
+                );
+            __debug
+                .field(
+                    "flags",
+                    &DebugRadix {
+                        digits: |buf: &mut DebugRadixBuf| {
+                            ::core::fmt::Write::write_fmt(
+                                buf,
+                                ::core::format_args!("{:x}", & self.flags),
+                            )
+                        },
+                        bits: ::core::mem::size_of_val(&self.flags) * 8,
+                        prefix: "0x",
+                        digit_bits: 4usize,
+                        group: 4usize,
+                    },
+                );
+            if __non_exhaustive {
+                __debug.finish_non_exhaustive()
+            } else {
+                __debug.finish()
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+pub struct Field {
+    name: &'static str,
+    #[debug = "0b{:08b}"]
+    bitmask: u8,
+}
+impl ::core::fmt::Debug for Field {
+    fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        {
+            let __debug = &mut fmt.debug_struct("Field");
+            let __non_exhaustive = false;
+            __debug.field("name", &self.name);
+            __debug
+                .field(
+                    "bitmask",
+                    &::core::format_args!("0b{__value:08b}", __value = * & self.bitmask),
+                );
+            if __non_exhaustive {
+                __debug.finish_non_exhaustive()
+            } else {
+                __debug.finish()
+            }
+        }
+    }
+}
@@ -0,0 +1,7 @@
+#[debug(transparent)]
+pub struct Id(u64);
+impl ::core::fmt::Debug for Id {
+    fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        ::core::fmt::Debug::fmt(&self.0, fmt)
+    }
+}
@@ -0,0 +1,6 @@
+pub struct Pair(u8, #[debug(skip)] u8);
+impl ::core::fmt::Debug for Pair {
+    fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        fmt.debug_tuple("Pair").field(&self.0).finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,28 @@
+#[debug(opaque, unsafe_bytes)]
+pub union Raw {
+    int: u32,
+    bytes: [u8; 4],
+}
+impl ::core::fmt::Debug for Raw {
+    fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        struct DebugBytes<'a>(&'a [u8]);
+        struct DebugByte(u8);
+        impl ::core::fmt::Debug for DebugByte {
+            fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                ::core::write!(fmt, "{:#04x}", self.0)
+            }
+        }
+        impl ::core::fmt::Debug for DebugBytes<'_> {
+            fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                fmt.debug_list().entries(self.0.iter().map(|b| DebugByte(*b))).finish()
+            }
+        }
+        let bytes = unsafe {
+            ::core::slice::from_raw_parts(
+                self as *const Self as *const u8,
+                ::core::mem::size_of::<Self>(),
+            )
+        };
+        fmt.debug_tuple("Raw").field(&DebugBytes(bytes)).finish()
+    }
+}
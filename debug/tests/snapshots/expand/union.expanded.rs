@@ -0,0 +1,10 @@
+#[debug(opaque)]
+pub union Bits {
+    int: u32,
+    float: f32,
+}
+impl ::core::fmt::Debug for Bits {
+    fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        fmt.debug_struct("Bits").finish_non_exhaustive()
+    }
+}
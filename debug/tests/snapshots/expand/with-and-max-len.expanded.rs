@@ -0,0 +1,68 @@
+pub struct Buffer {
+    #[debug(with = "hidden")]
+    token: u32,
+    #[debug(max_len = 8)]
+    bytes: Vec<u8>,
+}
+impl ::core::fmt::Debug for Buffer {
+    fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        struct DebugWith<F>(F);
+        impl<F> ::core::fmt::Debug for DebugWith<F>
+        where
+            F: ::core::ops::Fn(&mut ::core::fmt::Formatter) -> ::core::fmt::Result,
+        {
+            fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                (self.0)(fmt)
+            }
+        }
+        struct DebugTruncatedStr<'a>(&'a str, usize);
+        impl ::core::fmt::Debug for DebugTruncatedStr<'_> {
+            fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                match self.0.char_indices().nth(self.1) {
+                    ::core::option::Option::Some((end, _)) => {
+                        ::core::fmt::Debug::fmt(&self.0[..end], fmt)?;
+                        let more = self.0[end..].chars().count();
+                        ::core::write!(fmt, "\u{2026} ({} more)", more)
+                    }
+                    ::core::option::Option::None => ::core::fmt::Debug::fmt(self.0, fmt),
+                }
+            }
+        }
+        struct DebugTruncatedList<I>(I, usize);
+        impl<I> ::core::fmt::Debug for DebugTruncatedList<I>
+        where
+            I: ::core::clone::Clone + ::core::iter::ExactSizeIterator,
+            I::Item: ::core::fmt::Debug,
+        {
+            fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                let len = self.0.len();
+                let mut list = fmt.debug_list();
+                list.entries(self.0.clone().take(self.1));
+                if len > self.1 {
+                    list.entry(
+                        &::core::format_args!("\u{2026} ({} more)", len - self.1),
+                    );
+                }
+                list.finish()
+            }
+        }
+        {
+            let __debug = &mut fmt.debug_struct("Buffer");
+            let __non_exhaustive = false;
+            __debug
+                .field(
+                    "token",
+                    &DebugWith(|fmt: &mut ::core::fmt::Formatter| hidden(
+                        &self.token,
+                        fmt,
+                    )),
+                );
+            __debug.field("bytes", &DebugTruncatedList((&self.bytes).iter(), 8usize));
+            if __non_exhaustive {
+                __debug.finish_non_exhaustive()
+            } else {
+                __debug.finish()
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use proc_macro::TokenStream;
-use proc_macro2::{Group, Ident, Span, TokenStream as TokenStream2};
-use syn::{parse::Parse, Error, LitInt, Token};
+use proc_macro2::{Group, Ident, TokenStream as TokenStream2};
+use range::Range;
+use syn::{parse::Parse, Error, Token};
 
 #[proc_macro]
 pub fn seq(input: TokenStream) -> TokenStream {
@@ -12,10 +13,7 @@ pub fn seq(input: TokenStream) -> TokenStream {
 struct Seq {
     ident:       Ident,
     in_token:    Token![in],
-    lhs:         LitInt,
-    dot2_token:  Token![..],
-    eq_token:    Option<Token![=]>,
-    rhs:         LitInt,
+    range:       Range,
     brace_token: syn::token::Brace,
     tokens:      TokenStream2,
 }
@@ -25,10 +23,7 @@ impl Parse for Seq {
         let content;
         Ok(Seq { ident:       input.parse()?,
                  in_token:    input.parse()?,
-                 lhs:         input.parse()?,
-                 dot2_token:  input.parse()?,
-                 eq_token:    input.parse().ok(),
-                 rhs:         input.parse()?,
+                 range:       input.parse()?,
                  brace_token: syn::braced!(content in input),
                  tokens:      content.parse()?, })
     }
@@ -36,17 +31,16 @@ impl Parse for Seq {
 
 impl Seq {
     fn expand(self) -> TokenStream2 {
-        let Seq { ident, lhs, rhs, tokens, eq_token, .. } = self;
+        let Seq { ident, range, tokens, .. } = self;
         let buffer = syn::buffer::TokenBuffer::new2(tokens);
         let cursor = buffer.begin();
-        match (lhs.base10_parse(), rhs.base10_parse()) {
-            (Ok(lhs), Ok(rhs)) => {
-                let range = if eq_token.is_some() { (lhs..=rhs).into() } else { (lhs..rhs).into() };
-                repeat::SeqToken::new(cursor, &ident, range).token_stream()
+        if range.has_negative() {
+            if let Some(span) = replace::find_paste(cursor, &ident) {
+                let msg = "cannot paste a negative number into an identifier";
+                return Error::new(span, msg).to_compile_error();
             }
-            (Err(err), _) => lit_err(err, lhs.span()),
-            (Ok(_), Err(err)) => lit_err(err, rhs.span()),
         }
+        repeat::SeqToken::new(cursor, &ident, range).token_stream()
     }
 }
 
@@ -60,8 +54,3 @@ fn new_group(g: &Group, ts: TokenStream2) -> Group {
     group.set_span(g.span());
     group
 }
-
-fn lit_err(err: Error, span: Span) -> TokenStream2 {
-    let err = format!("`{}`\nOnly support `usize` type for now!", err);
-    Error::new(span, err).to_compile_error()
-}
\ No newline at end of file
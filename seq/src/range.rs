@@ -1,37 +1,288 @@
-//! 统一 Range<usize> 和 RangeInclusive<usize> 两种类型
+//! 统一 Range 和 RangeInclusive 两种类型，以及它们的 `.step_by(n)` 和 `.rev()`
+//!
+//! 范围的端点是带符号、可带后缀的整数字面值（如 `-4`、`0u8`），由 `RangeLit` 描述。
 
-pub type RangeLit = usize;
-type StdRange = std::ops::Range<RangeLit>;
-type StdRangeInc = std::ops::RangeInclusive<RangeLit>;
+use proc_macro2::{Delimiter, Group, Literal, Span, TokenTree};
+use std::rc::Rc;
+use syn::{parse::Parse, Error, Ident, LitInt, Token};
 
+// 整数字面值：值和后缀（`0u8` 的后缀为 `u8`，无后缀时为空字符串）
 #[derive(Clone)]
-pub enum Range {
-    Normal(StdRange),
-    Inclusive(StdRangeInc),
+pub struct RangeLit {
+    pub value:  i128,
+    pub suffix: Rc<str>,
+    pub span:   Span,
 }
 
-impl Iterator for Range {
-    type Item = RangeLit;
+// 端点可以是整数的 `+ - * / %` 运算（如 `2*8`、`-(4 + 4)`），在展开时求值；
+// `macro_rules!` 中的 `$n:expr` 以不可见分隔符的 Group 传入，当作带括号的表达式
+impl Parse for RangeLit {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        parse_sum(input)
+    }
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self {
-            Range::Normal(i) => i.next(),
-            Range::Inclusive(i) => i.next(),
+// sum := product (('+' | '-') product)*
+fn parse_sum(input: syn::parse::ParseStream) -> syn::Result<RangeLit> {
+    let mut lhs = parse_product(input)?;
+    loop {
+        let op = if input.peek(Token![+]) {
+            input.parse::<Token![+]>()?;
+            '+'
+        } else if input.peek(Token![-]) {
+            input.parse::<Token![-]>()?;
+            '-'
+        } else {
+            return Ok(lhs);
+        };
+        lhs = lhs.binary(op, parse_product(input)?)?;
+    }
+}
+
+// product := unary (('*' | '/' | '%') unary)*
+fn parse_product(input: syn::parse::ParseStream) -> syn::Result<RangeLit> {
+    let mut lhs = parse_unary(input)?;
+    loop {
+        let op = if input.peek(Token![*]) {
+            input.parse::<Token![*]>()?;
+            '*'
+        } else if input.peek(Token![/]) {
+            input.parse::<Token![/]>()?;
+            '/'
+        } else if input.peek(Token![%]) {
+            input.parse::<Token![%]>()?;
+            '%'
+        } else {
+            return Ok(lhs);
+        };
+        lhs = lhs.binary(op, parse_unary(input)?)?;
+    }
+}
+
+// unary := '-' unary | 整数字面值 | '(' sum ')' | 不可见分隔符的 Group
+fn parse_unary(input: syn::parse::ParseStream) -> syn::Result<RangeLit> {
+    if input.peek(Token![-]) {
+        input.parse::<Token![-]>()?;
+        let lit = parse_unary(input)?;
+        let value = lit.value.checked_neg().ok_or_else(|| overflow(lit.span))?;
+        return Ok(RangeLit { value, ..lit });
+    }
+    if input.peek(syn::token::Paren) {
+        let content;
+        syn::parenthesized!(content in input);
+        return parse_all(&content);
+    }
+    let group = input.step(|cursor| match cursor.group(proc_macro2::Delimiter::None) {
+                         Some((content, _, rest)) => Ok((Some(content.token_stream()), rest)),
+                         None => Ok((None, *cursor)),
+                     })?;
+    if let Some(tokens) = group {
+        return syn::parse::Parser::parse2(parse_all, tokens);
+    }
+    if input.peek(Ident) {
+        let ident: Ident = input.parse()?;
+        let msg = format!("expected an integer literal, the value of `{}` is not known when seq! is expanded",
+                          ident);
+        return Err(Error::new(ident.span(), msg));
+    }
+    let lit: LitInt = input.parse()?;
+    Ok(RangeLit { value:  lit.base10_parse()?,
+                  suffix: lit.suffix().into(),
+                  span:   lit.span(), })
+}
+
+// 括号或 Group 中必须恰好是一个表达式
+fn parse_all(input: syn::parse::ParseStream) -> syn::Result<RangeLit> {
+    let lit = parse_sum(input)?;
+    if !input.is_empty() {
+        return Err(input.error("expected an integer expression"));
+    }
+    Ok(lit)
+}
+
+fn overflow(span: Span) -> Error {
+    Error::new(span, "integer overflow while evaluating the bound")
+}
+
+impl RangeLit {
+    // 两个值的后缀必须一致；只有一个带后缀时，另一个沿用它
+    fn merge_suffix(&self, rhs: &RangeLit) -> syn::Result<Rc<str>> {
+        match (self.suffix.is_empty(), rhs.suffix.is_empty()) {
+            (_, true) => Ok(self.suffix.clone()),
+            (true, false) => Ok(rhs.suffix.clone()),
+            (false, false) if self.suffix == rhs.suffix => Ok(self.suffix.clone()),
+            (false, false) => {
+                let msg = format!("mismatched suffixes `{}` and `{}`", self.suffix, rhs.suffix);
+                Err(Error::new(rhs.span, msg))
+            }
         }
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        match self {
-            Range::Normal(i) => i.size_hint(),
-            Range::Inclusive(i) => i.size_hint(),
+    fn binary(self, op: char, rhs: RangeLit) -> syn::Result<Self> {
+        let suffix = self.merge_suffix(&rhs)?;
+        let value = match op {
+            '+' => self.value.checked_add(rhs.value),
+            '-' => self.value.checked_sub(rhs.value),
+            '*' => self.value.checked_mul(rhs.value),
+            _ if rhs.value == 0 => return Err(Error::new(rhs.span, "attempt to divide by zero")),
+            '/' => self.value.checked_div(rhs.value),
+            _ => self.value.checked_rem(rhs.value),
+        };
+        Ok(RangeLit { value: value.ok_or_else(|| overflow(rhs.span))?,
+                      suffix,
+                      span: self.span })
+    }
+
+    // 值必须在后缀类型的范围内；无后缀时类型由上下文推断，不检查
+    fn check_suffix(&self, suffix: &str) -> syn::Result<()> {
+        let (min, max) = match suffix {
+            "i8" => (i8::MIN as i128, i8::MAX as i128),
+            "i16" => (i16::MIN as i128, i16::MAX as i128),
+            "i32" => (i32::MIN as i128, i32::MAX as i128),
+            "i64" | "isize" => (i64::MIN as i128, i64::MAX as i128),
+            "i128" => (i128::MIN, i128::MAX),
+            "u8" => (0, u8::MAX as i128),
+            "u16" => (0, u16::MAX as i128),
+            "u32" => (0, u32::MAX as i128),
+            "u64" | "usize" => (0, u64::MAX as i128),
+            "u128" => (0, i128::MAX),
+            _ => return Ok(()),
+        };
+        if self.value < 0 && min == 0 {
+            return Err(Error::new(self.span, format!("`{}` cannot be negative", suffix)));
+        }
+        if self.value < min || self.value > max {
+            let msg = format!("`{}` is out of range for `{}`", self.value, suffix);
+            return Err(Error::new(self.span, msg));
+        }
+        Ok(())
+    }
+
+    // 生成与范围端点后缀相同的字面值；负数是 `-` 和字面值两个 token，加上括号，
+    // 使 `N.abs()` 是 `(-3).abs()` 而不是 `-(3.abs())`（不可见分隔符的 Group 不起作用）
+    pub fn token(&self) -> TokenTree {
+        let lit: Literal = format!("{}{}", self.value, self.suffix).parse().expect("an integer literal");
+        if self.value < 0 {
+            Group::new(Delimiter::Parenthesis, TokenTree::from(lit).into()).into()
+        } else {
+            lit.into()
+        }
+    }
+}
+
+// 等差数列：从 `first` 开始，每次加 `step`，共 `len` 个值
+// - `step` 的绝对值是步长（`.step_by(n)`），符号是方向（`.rev()` 取反）
+// - 任意顺序的 `.step_by(n)` 和 `.rev()` 组合仍是等差数列，与标准库的迭代顺序一致
+#[derive(Clone)]
+pub struct Range {
+    first:  i128,
+    step:   i128,
+    len:    i128,
+    suffix: Rc<str>,
+}
+
+impl Parse for Range {
+    // `lhs..rhs`、`lhs..=rhs`，或 `(范围)` 之后跟着任意个 `.step_by(n)`、`.rev()`
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if !is_parenthesized_range(input) {
+            let lhs = input.parse()?;
+            input.parse::<Token![..]>()?;
+            let eq_token: Option<Token![=]> = input.parse()?;
+            let rhs = input.parse()?;
+            return Range::new(lhs, rhs, eq_token.is_some());
+        }
+        let content;
+        syn::parenthesized!(content in input);
+        let mut range: Range = content.parse()?;
+        while input.peek(Token![.]) {
+            input.parse::<Token![.]>()?;
+            let method: Ident = input.parse()?;
+            let args;
+            syn::parenthesized!(args in input);
+            range = match method.to_string().as_str() {
+                "step_by" => {
+                    let step: LitInt = args.parse()?;
+                    match step.base10_parse::<usize>()? {
+                        0 => return Err(Error::new(step.span(), "the step must be positive")),
+                        n => range.step_by(n as i128),
+                    }
+                }
+                "rev" => range.rev(),
+                _ => return Err(Error::new(method.span(), "expected `step_by` or `rev`")),
+            };
+            if !args.is_empty() {
+                return Err(args.error("unexpected argument"));
+            }
         }
+        Ok(range)
     }
 }
 
-impl From<StdRange> for Range {
-    fn from(r: StdRange) -> Self { Range::Normal(r) }
+// 区分 `(0..8).rev()` 与以括号开头的端点，如 `(1 + 1)..8`
+fn is_parenthesized_range(input: syn::parse::ParseStream) -> bool {
+    let parse = |input: syn::parse::ParseStream| -> syn::Result<()> {
+        let content;
+        syn::parenthesized!(content in input);
+        content.parse::<RangeLit>()?;
+        content.parse::<Token![..]>()?;
+        Ok(())
+    };
+    input.peek(syn::token::Paren) && parse(&input.fork()).is_ok()
+}
+
+impl Range {
+    // 两个端点的后缀必须一致；只有一端带后缀时，另一端沿用它，且两端都要在它的范围内
+    pub fn new(lhs: RangeLit, rhs: RangeLit, inclusive: bool) -> syn::Result<Self> {
+        let suffix = lhs.merge_suffix(&rhs)?;
+        lhs.check_suffix(&suffix)?;
+        rhs.check_suffix(&suffix)?;
+        let end = if inclusive { rhs.value.saturating_add(1) } else { rhs.value };
+        Ok(Range { first: lhs.value,
+                   step: 1,
+                   len: end.saturating_sub(lhs.value).max(0),
+                   suffix })
+    }
+
+    // 保留第一个值，之后每 n 个取一个
+    fn step_by(self, n: i128) -> Self {
+        Range { step: self.step * n,
+                len: (self.len + n - 1) / n,
+                ..self }
+    }
+
+    // 从最后一个值开始反向迭代
+    fn rev(self) -> Self {
+        if self.len == 0 {
+            return self;
+        }
+        Range { first: self.first + self.step * (self.len - 1),
+                step: -self.step,
+                ..self }
+    }
+
+    // 范围内是否有负数（负数无法拼接到标识符中）；只需检查首尾两个值
+    pub fn has_negative(&self) -> bool {
+        self.len > 0 && (self.first < 0 || self.first + self.step * (self.len - 1) < 0)
+    }
 }
 
-impl From<StdRangeInc> for Range {
-    fn from(r: StdRangeInc) -> Self { Range::Inclusive(r) }
+impl Iterator for Range {
+    type Item = RangeLit;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let value = self.first;
+        self.first += self.step;
+        self.len -= 1;
+        Some(RangeLit { value,
+                        suffix: self.suffix.clone(),
+                        span: Span::call_site() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = usize::try_from(self.len).ok();
+        (len.unwrap_or(usize::MAX), len)
+    }
 }
@@ -29,7 +29,7 @@ impl<'c, 'i> SeqToken<'c, 'i> {
     }
 
     fn repeat_and_replace(&mut self, cursor: Cursor) {
-        let iter = self.range.clone().map(|lit| crate::replace::replace(cursor, self.ident, lit));
+        let iter = self.range.clone().map(|lit| crate::replace::replace(cursor, self.ident, &lit));
         self.output.push(TokenStream2::from_iter(iter));
     }
 
@@ -47,10 +47,12 @@ impl<'c, 'i> SeqToken<'c, 'i> {
                         }
                     }
                 }
-                TT::Group(g) => {
+                TT::Group(g)
                     if SeqToken::new(TokenBuffer::new2(g.stream()).begin(),
-                                              self.ident,
-                                              self.range.clone()).search_repeat_tag() { return true; }
+                                     self.ident,
+                                     self.range.clone()).search_repeat_tag() =>
+                {
+                    return true
                 }
                 _ => (),
             }
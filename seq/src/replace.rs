@@ -1,24 +1,24 @@
 use crate::range::RangeLit;
-use proc_macro2::{Group, Ident, Literal, TokenStream as TokenStream2, TokenTree as TT};
+use proc_macro2::{Group, Ident, Span, TokenStream as TokenStream2, TokenTree as TT};
 use syn::buffer::{Cursor, TokenBuffer};
 
 // 将所有 ident 替换成字面值、`prefix~ident` 替换成 `prefix字面值`
-pub fn replace(mut cursor: Cursor, ident: &Ident, lit: RangeLit) -> TokenStream2 {
+pub fn replace(mut cursor: Cursor, ident: &Ident, lit: &RangeLit) -> TokenStream2 {
     let mut ts = Vec::with_capacity(32);
     while let Some((token, cur)) = cursor.token_tree() {
         cursor = cur;
         let t = match token {
             TT::Ident(i) => {
                 if &i == ident {
-                    // TODO: 字面值暂时只假设为 usize（由 RangeLit 统一描述），它可以通过所有测试
-                    //       真实场景中，需要放宽这个类型（见 https://docs.rs/seq-macro ）
-                    Literal::usize_unsuffixed(lit).into()
+                    // 字面值沿用范围端点的后缀，如 `0u8..4u8` 生成 `0u8`、`1u8` 等
+                    lit.token()
                 } else if let Some((matched, cur)) =
                     search_tidle_ident(i.clone().into(), cur, ident, lit, &mut ts)
                 {
                     cursor = cur;
                     if matched {
-                        quote::format_ident!("{}{}", i, lit).into()
+                        // 负数已在展开前被拒绝（见 find_paste），这里的值都是非负的
+                        quote::format_ident!("{}{}", i, lit.value.unsigned_abs()).into()
                     } else {
                         continue;
                     }
@@ -34,7 +34,30 @@ pub fn replace(mut cursor: Cursor, ident: &Ident, lit: RangeLit) -> TokenStream2
     TokenStream2::from_iter(ts)
 }
 
-fn match_group(g: &Group, ident: &Ident, lit: RangeLit) -> TT {
+// 查找 `~ident` 拼接，返回 `ident` 的 span
+pub fn find_paste(mut cursor: Cursor, ident: &Ident) -> Option<Span> {
+    while let Some((token, cur)) = cursor.token_tree() {
+        match &token {
+            TT::Punct(p) if p.as_char() == '~' => {
+                if let Some((TT::Ident(i), _)) = cur.token_tree() {
+                    if &i == ident {
+                        return Some(i.span());
+                    }
+                }
+            }
+            TT::Group(g) => {
+                if let Some(span) = find_paste(TokenBuffer::new2(g.stream()).begin(), ident) {
+                    return Some(span);
+                }
+            }
+            _ => (),
+        }
+        cursor = cur;
+    }
+    None
+}
+
+fn match_group(g: &Group, ident: &Ident, lit: &RangeLit) -> TT {
     let tokens = replace(TokenBuffer::new2(g.stream()).begin(), ident, lit);
     crate::new_group(g, tokens).into()
 }
@@ -46,9 +69,9 @@ type Search<'c> = Option<(bool, Cursor<'c>)>;
 // - Some((false, cur)) 表示未找到，且把捕获的标记添加到 ts
 // - None 在 search_ident 函数中表示遇到标记流结束（虽然它最终不返回 None）； 在
 //   search_tidle_ident 函数中表示 **i 与此宏功能无关**，或者标记流结束
-fn search_tidle_ident<'c>(i: TT, cursor: Cursor<'c>, ident: &Ident, lit: RangeLit, ts: &mut Vec<TT>)
+fn search_tidle_ident<'c>(i: TT, cursor: Cursor<'c>, ident: &Ident, lit: &RangeLit, ts: &mut Vec<TT>)
                           -> Search<'c> {
-    fn search_ident<'c>(i: TT, tidle: TT, cursor: Cursor<'c>, ident: &Ident, lit: RangeLit,
+    fn search_ident<'c>(i: TT, tidle: TT, cursor: Cursor<'c>, ident: &Ident, lit: &RangeLit,
                         ts: &mut Vec<TT>)
                         -> Search<'c> {
         if let Some((token, cur)) = cursor.token_tree() {
@@ -0,0 +1,58 @@
+// The bounds of the range are not limited to unsigned numbers. A negative
+// lower bound counts up through zero:
+//
+//     seq!(N in -4..4 { ... })
+//
+// A bound may also carry an integer suffix, like 0u8..16u8. Every literal
+// substituted into the body then has the same suffix, so that it has the
+// intended type wherever it ends up. When only one of the bounds is suffixed
+// the other one uses the same suffix.
+//
+// A negative number is substituted as a whole, as if it were in parentheses:
+// N.abs() with N = -3 is 3, not -3.
+//
+// Pasting a number into an identifier with `~N` keeps working, without the
+// suffix: Variant~N becomes Variant0, not Variant0u8.
+
+use seq::seq;
+
+fn type_name<T>(_: T) -> &'static str {
+    std::any::type_name::<T>()
+}
+
+seq!(N in 0u8..3u8 {
+    enum E {
+        #(
+            Variant~N,
+        )*
+    }
+});
+
+fn main() {
+    let mut sum = 0;
+    seq!(N in -4..4 {
+        sum += N;
+    });
+    assert_eq!(sum, -4);
+
+    let values = seq!(N in -2..=2 { [#(N * 10,)*] });
+    assert_eq!(values, [-20, -10, 0, 10, 20]);
+
+    let abs = seq!(N in -3i32..-1 { [#(N.abs(),)*] });
+    assert_eq!(abs, [3, 2]);
+
+    seq!(N in 0u8..16u8 {
+        #(
+            assert_eq!(type_name(N), "u8");
+        )*
+    });
+
+    seq!(N in -3i64..=3 {
+        #(
+            assert_eq!(type_name(N), "i64");
+        )*
+    });
+
+    let _ = E::Variant0;
+    let _ = E::Variant2;
+}
@@ -0,0 +1,23 @@
+// Bounds that cannot describe a range of integers of one type are rejected
+// with an error pointing at the offending bound: two different suffixes, or a
+// bound that does not fit in the type of the suffix. The suffix of one bound
+// applies to the other one too, so -1..2u8 is rejected like -1u8..2u8.
+//
+// A negative number cannot be pasted into an identifier, because Variant-1 is
+// not an identifier. The error points at the pasted `N`.
+
+use seq::seq;
+
+seq!(N in 0u8..16u16 {});
+
+seq!(N in -1u32..1 {});
+
+seq!(N in -1..2u8 {});
+
+seq!(N in 254u8..258u8 {});
+
+seq!(N in -2..2 {
+    struct Value~N;
+});
+
+fn main() {}
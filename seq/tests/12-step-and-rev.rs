@@ -0,0 +1,44 @@
+// Register maps are laid out with a stride: the registers of a peripheral sit
+// at offsets 0, 8, 16, ... To generate them, the range may be wrapped in
+// parentheses and followed by the same adapters as a range in Rust:
+//
+//     seq!(N in (0..64).step_by(8) { ... })
+//     seq!(N in (0..8).rev() { ... })
+//
+// The adapters can be chained and they produce the numbers in the same order
+// as the iterator they are named after: (0..8).step_by(3).rev() counts 6, 3, 0
+// while (0..8).rev().step_by(3) counts 7, 4, 1.
+
+use seq::seq;
+
+seq!(N in (0..64).step_by(8) {
+    #[allow(dead_code)]
+    enum Register {
+        #(
+            Offset~N = N,
+        )*
+    }
+});
+
+fn main() {
+    assert_eq!(Register::Offset0 as u8, 0);
+    assert_eq!(Register::Offset56 as u8, 56);
+
+    let offsets = seq!(N in (0..64).step_by(8) { [#(N,)*] });
+    assert_eq!(offsets, [0, 8, 16, 24, 32, 40, 48, 56]);
+
+    let countdown = seq!(N in (0..8).rev() { [#(N,)*] });
+    assert_eq!(countdown, [7, 6, 5, 4, 3, 2, 1, 0]);
+
+    let inclusive = seq!(N in (0..=16).step_by(4) { [#(N,)*] });
+    assert_eq!(inclusive, [0, 4, 8, 12, 16]);
+
+    let step_then_rev = seq!(N in (0..8).step_by(3).rev() { [#(N,)*] });
+    assert_eq!(step_then_rev, [6, 3, 0]);
+
+    let rev_then_step = seq!(N in (0..8).rev().step_by(3) { [#(N,)*] });
+    assert_eq!(rev_then_step, [7, 4, 1]);
+
+    let signed = seq!(N in (-4i8..4i8).step_by(2).rev() { [#(N,)*] });
+    assert_eq!(signed, [2i8, 0, -2, -4]);
+}
@@ -0,0 +1,12 @@
+// Only the step_by and rev adapters are supported, and a step of zero would
+// never advance, like Iterator::step_by which panics on it.
+
+use seq::seq;
+
+seq!(N in (0..8).skip(1) {});
+
+seq!(N in (0..8).step_by(0) {});
+
+seq!(N in (0..8).rev(1) {});
+
+fn main() {}
@@ -0,0 +1,44 @@
+// The bounds do not have to be single literals. Simple constant arithmetic
+// with + - * / % and parentheses is evaluated when the macro expands:
+//
+//     seq!(N in 0..2*8 { ... })
+//
+// This is what makes it possible to compute a bound in a macro_rules! macro
+// that forwards its input as an `$n:expr` fragment. Unlike `$n:literal` in
+// test 09, an expression fragment reaches seq! wrapped in a group with
+// invisible delimiters, which keeps it together as one operand: with
+// `$n = 1 + 1`, the bound `$n * 2` is 4 and not 3.
+//
+// Named constants still cannot be used, for the reason explained in test 09.
+
+use seq::seq;
+
+macro_rules! doubled {
+    ($n:expr) => {
+        seq!(N in 0..$n * 2 { [#(N,)*] })
+    };
+}
+
+macro_rules! registers {
+    ($count:expr, $stride:expr) => {
+        seq!(N in (0..$count * $stride).step_by($stride) { [#(N,)*] })
+    };
+}
+
+fn main() {
+    let sixteen = seq!(N in 0..2*8 { [#(N,)*] });
+    assert_eq!(sixteen.len(), 16);
+
+    assert_eq!(doubled!(1 + 1), [0, 1, 2, 3]);
+
+    let grouped = seq!(N in (1 + 1)..(10 - 4) / 2 + 3 { [#(N,)*] });
+    assert_eq!(grouped, [2, 3, 4, 5]);
+
+    let negative = seq!(N in -(2 + 1)..=10 % 4 { [#(N,)*] });
+    assert_eq!(negative, [-3, -2, -1, 0, 1, 2]);
+
+    let suffixed = seq!(N in 0..2u8 * 2 { [#(N,)*] });
+    assert_eq!(suffixed, [0u8, 1, 2, 3]);
+
+    assert_eq!(registers!(4, 8), [0, 8, 16, 24]);
+}
@@ -0,0 +1,18 @@
+// A bound that cannot be evaluated while the macro expands is an error
+// pointing at the part of the expression that could not be evaluated.
+
+use seq::seq;
+
+const LEN: usize = 8;
+
+seq!(N in 0..LEN {});
+
+seq!(N in 0..8 / (2 - 2) {});
+
+seq!(N in 0..4u8 * 2u16 {});
+
+seq!(N in 0..170141183460469231731687303715884105727 + 1 {});
+
+fn main() {
+    let _ = LEN;
+}
@@ -10,4 +10,10 @@ fn tests() {
     t.pass("tests/07-inclusive-range.rs");
     t.compile_fail("tests/08-ident-span.rs");
     t.pass("tests/09-interaction-with-macrorules.rs");
+    t.pass("tests/10-signed-and-suffixed.rs");
+    t.compile_fail("tests/11-literal-errors.rs");
+    t.pass("tests/12-step-and-rev.rs");
+    t.compile_fail("tests/13-step-and-rev-errors.rs");
+    t.pass("tests/14-bound-expressions.rs");
+    t.compile_fail("tests/15-bound-expression-errors.rs");
 }